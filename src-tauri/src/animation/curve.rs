@@ -0,0 +1,325 @@
+/// A CSS-style `cubic-bezier(x1, y1, x2, y2)` timing function, solved the way
+/// WebKit's `UnitBezier` does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    cx: f64,
+    bx: f64,
+    ax: f64,
+    cy: f64,
+    by: f64,
+    ay: f64,
+}
+
+impl CubicBezier {
+    /// `x1` and `x2` are clamped to 0–1 so time only moves forward; `y1` and
+    /// `y2` may overshoot.
+    pub fn new(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        let (x1, x2) = (x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0));
+        let cx = 3.0 * x1;
+        let bx = 3.0 * (x2 - x1) - cx;
+        let cy = 3.0 * y1;
+        let by = 3.0 * (y2 - y1) - cy;
+        Self {
+            cx,
+            bx,
+            ax: 1.0 - cx - bx,
+            cy,
+            by,
+            ay: 1.0 - cy - by,
+        }
+    }
+
+    /// From config's `[x1, y1, x2, y2]`; `None` unless there are exactly four.
+    pub fn from_points(points: &[f64]) -> Option<Self> {
+        match *points {
+            [x1, y1, x2, y2] => Some(Self::new(x1, y1, x2, y2)),
+            _ => None,
+        }
+    }
+
+    /// Eased progress at `x`, the fraction of the duration elapsed.
+    pub fn ease(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        if x >= 1.0 {
+            return 1.0;
+        }
+        self.sample_y(self.solve_t(x))
+    }
+
+    fn sample_x(&self, t: f64) -> f64 {
+        ((self.ax * t + self.bx) * t + self.cx) * t
+    }
+
+    fn sample_y(&self, t: f64) -> f64 {
+        ((self.ay * t + self.by) * t + self.cy) * t
+    }
+
+    fn sample_dx(&self, t: f64) -> f64 {
+        (3.0 * self.ax * t + 2.0 * self.bx) * t + self.cx
+    }
+
+    // The curve parameter where x(t) = x: Newton's method, then bisection if
+    // the slope is too flat for it to converge
+    fn solve_t(&self, x: f64) -> f64 {
+        const EPSILON: f64 = 1e-7;
+        let mut t = x;
+        for _ in 0..8 {
+            let error = self.sample_x(t) - x;
+            if error.abs() < EPSILON {
+                return t;
+            }
+            let slope = self.sample_dx(t);
+            if slope.abs() < 1e-6 {
+                break;
+            }
+            t -= error / slope;
+        }
+
+        let (mut lo, mut hi) = (0.0, 1.0);
+        t = x;
+        while lo < hi {
+            let sample = self.sample_x(t);
+            if (sample - x).abs() < EPSILON {
+                break;
+            }
+            if x > sample {
+                lo = t;
+            } else {
+                hi = t;
+            }
+            let next = (lo + hi) / 2.0;
+            if next == t {
+                break;
+            }
+            t = next;
+        }
+        t
+    }
+}
+
+/// How close to the target, as a fraction of the distance travelled, a spring
+/// has to stay to count as settled.
+const SPRING_REST: f64 = 0.001;
+
+/// Longest a spring may take to settle, in seconds. Barely damped springs would
+/// otherwise ring for minutes.
+pub const MAX_SETTLE_TIME: f64 = 10.0;
+
+/// A damped spring pulling from 0 towards 1, the model Core Animation's
+/// `CASpringAnimation` uses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub stiffness: f64,
+    pub damping: f64,
+    pub mass: f64,
+    /// Velocity when starting from rest, in units of the distance per second.
+    pub initial_velocity: f64,
+}
+
+impl Default for Spring {
+    /// Matches the Swift animator's spring on macOS 14+.
+    fn default() -> Self {
+        Self {
+            stiffness: 300.0,
+            damping: 30.0,
+            mass: 1.0,
+            initial_velocity: 0.0,
+        }
+    }
+}
+
+impl Spring {
+    fn natural_frequency(&self) -> f64 {
+        (self.stiffness / self.mass.max(f64::EPSILON)).sqrt()
+    }
+
+    fn damping_ratio(&self) -> f64 {
+        self.damping / (2.0 * (self.stiffness * self.mass).max(f64::EPSILON).sqrt())
+    }
+
+    /// Position `t` seconds in, starting at 0 with `velocity` (distance per
+    /// second, in units of the whole distance).
+    pub fn position(&self, t: f64, velocity: f64) -> f64 {
+        1.0 + self.offset(t, velocity)
+    }
+
+    // Displacement from the target, solved analytically
+    fn offset(&self, t: f64, velocity: f64) -> f64 {
+        let omega = self.natural_frequency();
+        let zeta = self.damping_ratio();
+        let x0 = -1.0;
+        if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let b = (velocity + zeta * omega * x0) / omega_d;
+            (-zeta * omega * t).exp() * (x0 * (omega_d * t).cos() + b * (omega_d * t).sin())
+        } else if zeta == 1.0 {
+            (-omega * t).exp() * (x0 + (velocity + omega * x0) * t)
+        } else {
+            let omega_h = omega * (zeta * zeta - 1.0).sqrt();
+            let b = (velocity + zeta * omega * x0) / omega_h;
+            (-zeta * omega * t).exp() * (x0 * (omega_h * t).cosh() + b * (omega_h * t).sinh())
+        }
+    }
+
+    /// Seconds until the spring stays within [`SPRING_REST`] of the target, at
+    /// most [`MAX_SETTLE_TIME`].
+    pub fn settle_time(&self, velocity: f64) -> f64 {
+        let omega = self.natural_frequency();
+        let zeta = self.damping_ratio();
+        if zeta < 1.0 {
+            // The oscillation's envelope bounds the offset from then on
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let b = (velocity - zeta * omega) / omega_d;
+            let amplitude = (1.0 + b * b).sqrt();
+            // Infinite without damping
+            return ((amplitude / SPRING_REST).ln() / (zeta * omega)).clamp(0.0, MAX_SETTLE_TIME);
+        }
+        // Without oscillation the offset shrinks steadily once it's small
+        const STEP: f64 = 1.0 / 240.0;
+        let mut t = 0.0;
+        while t < MAX_SETTLE_TIME {
+            if self.offset(t, velocity).abs() < SPRING_REST
+                && self.offset(t + STEP, velocity).abs() < SPRING_REST
+            {
+                return t;
+            }
+            t += STEP;
+        }
+        MAX_SETTLE_TIME
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XS: [f64; 5] = [0.1, 0.25, 0.5, 0.75, 0.9];
+
+    fn assert_eases(curve: CubicBezier, expected: [f64; 5]) {
+        for (x, y) in XS.into_iter().zip(expected) {
+            let eased = curve.ease(x);
+            assert!((eased - y).abs() < 1e-4, "ease({x}) = {eased}, not {y}");
+        }
+    }
+
+    #[test]
+    fn css_ease() {
+        assert_eases(
+            CubicBezier::new(0.25, 0.1, 0.25, 1.0),
+            [0.094796, 0.408511, 0.802403, 0.960459, 0.994316],
+        );
+    }
+
+    #[test]
+    fn css_ease_in_out() {
+        assert_eases(
+            CubicBezier::new(0.42, 0.0, 0.58, 1.0),
+            [0.019722, 0.129162, 0.5, 0.870838, 0.980278],
+        );
+    }
+
+    #[test]
+    fn css_linear() {
+        assert_eases(CubicBezier::new(0.0, 0.0, 1.0, 1.0), XS);
+    }
+
+    #[test]
+    fn ease_pins_the_ends() {
+        let curve = CubicBezier::new(0.25, 0.1, 0.25, 1.0);
+        assert_eq!(curve.ease(-0.5), 0.0);
+        assert_eq!(curve.ease(0.0), 0.0);
+        assert_eq!(curve.ease(1.0), 1.0);
+        assert_eq!(curve.ease(1.5), 1.0);
+    }
+
+    #[test]
+    fn from_points_needs_four() {
+        assert_eq!(
+            CubicBezier::from_points(&[0.42, 0.0, 0.58, 1.0]),
+            Some(CubicBezier::new(0.42, 0.0, 0.58, 1.0))
+        );
+        assert_eq!(CubicBezier::from_points(&[0.42, 0.0, 0.58]), None);
+    }
+
+    fn damped(stiffness: f64, damping: f64) -> Spring {
+        Spring {
+            stiffness,
+            damping,
+            ..Spring::default()
+        }
+    }
+
+    // Stays within SPRING_REST of the target from `settle_time` on
+    fn assert_settles(spring: Spring, velocity: f64) -> f64 {
+        let settle = spring.settle_time(velocity);
+        for i in 0..=400 {
+            let t = settle + i as f64 * 0.005;
+            let offset = spring.position(t, velocity) - 1.0;
+            assert!(offset.abs() < SPRING_REST, "{offset} off at {t}s");
+        }
+        settle
+    }
+
+    #[test]
+    fn underdamped_spring_overshoots_and_settles() {
+        // The default: ω = √300, ζ = √3 / 2
+        let spring = Spring::default();
+        assert_eq!(spring.position(0.0, 0.0), 0.0);
+        for (t, expected) in [(0.05, 0.227923), (0.1, 0.561044), (0.2, 0.922879)] {
+            assert!((spring.position(t, 0.0) - expected).abs() < 1e-4);
+        }
+
+        let settle = assert_settles(spring, 0.0);
+        assert!((settle - 2000f64.ln() / 15.0).abs() < 1e-9);
+
+        let bouncy = damped(300.0, 10.0);
+        assert!((0..100).any(|i| bouncy.position(i as f64 * 0.01, 0.0) > 1.05));
+        assert_settles(bouncy, 0.0);
+    }
+
+    #[test]
+    fn critically_damped_spring_never_overshoots() {
+        // ζ = 20 / (2√100) = 1
+        let spring = damped(100.0, 20.0);
+        assert!((spring.position(0.1, 0.0) - 0.264241).abs() < 1e-4);
+        assert!((spring.position(0.2, 0.0) - 0.593994).abs() < 1e-4);
+        assert!((0..200).all(|i| spring.position(i as f64 * 0.01, 0.0) <= 1.0));
+
+        // The stepped search returns the first time it's settled
+        let settle = assert_settles(spring, 0.0);
+        let before = spring.position(settle - 1.0 / 240.0, 0.0);
+        assert!((before - 1.0).abs() >= SPRING_REST);
+    }
+
+    #[test]
+    fn overdamped_spring_creeps_in() {
+        let spring = damped(100.0, 60.0);
+        let positions: Vec<f64> = (0..100)
+            .map(|i| spring.position(i as f64 * 0.02, 0.0))
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(positions.iter().all(|&p| p <= 1.0));
+        let settle = assert_settles(spring, 0.0);
+        assert!(settle > Spring::default().settle_time(0.0));
+    }
+
+    #[test]
+    fn velocity_carries_into_the_spring() {
+        let spring = Spring::default();
+        // Heading away from the target first
+        assert!(spring.position(0.01, -20.0) < 0.0);
+        assert!(spring.position(0.01, 20.0) > spring.position(0.01, 0.0));
+        assert_settles(spring, -20.0);
+        assert_settles(spring, 20.0);
+    }
+
+    #[test]
+    fn settle_time_is_capped() {
+        assert_eq!(damped(300.0, 0.0).settle_time(0.0), MAX_SETTLE_TIME);
+        assert_eq!(damped(300.0, 1e-6).settle_time(0.0), MAX_SETTLE_TIME);
+        // Heavily overdamped springs crawl
+        assert_eq!(damped(1.0, 1e6).settle_time(0.0), MAX_SETTLE_TIME);
+    }
+}
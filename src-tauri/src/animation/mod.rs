@@ -0,0 +1,11 @@
+pub mod curve;
+pub mod solver;
+// Only the window-resizing controller animates in Rust; the Swift animator
+// plays solved curves
+#[cfg(not(target_os = "macos"))]
+pub mod timeline;
+
+pub use curve::{CubicBezier, Spring};
+pub use solver::SolvedAnimations;
+#[cfg(not(target_os = "macos"))]
+pub use timeline::{Easing, Timeline};
@@ -0,0 +1,211 @@
+use super::{CubicBezier, Spring};
+use crate::config::{AnimationConfig, AnimationModel};
+use serde::Serialize;
+
+/// Samples per second of a solved curve.
+pub const SAMPLE_RATE: f64 = 120.0;
+
+/// An [`AnimationModel`] solved ahead of time, so Swift and the frontend play
+/// exactly the motion Rust does without their own bezier or spring maths.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct AnimationCurve {
+    /// Seconds until the animation is over (springs: until it settles).
+    pub settle_time: f64,
+    /// Progress from 0 to 1, evenly spaced from 0 to `settle_time` inclusive.
+    /// Springs can overshoot past 1.
+    pub samples: Vec<f64>,
+}
+
+/// The solved `animation.expand` and `animation.collapse`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SolvedAnimations {
+    pub expand: AnimationCurve,
+    pub collapse: AnimationCurve,
+}
+
+impl SolvedAnimations {
+    pub fn solve(config: &AnimationConfig) -> Self {
+        Self {
+            expand: config.expand.value.solve(),
+            collapse: config.collapse.value.solve(),
+        }
+    }
+}
+
+impl AnimationModel {
+    pub fn solve(&self) -> AnimationCurve {
+        match self {
+            AnimationModel::Bezier { duration, points } => {
+                let curve = bezier_or_ease(points);
+                let duration = duration.max(0.0);
+                sample(duration, |t| {
+                    if duration > 0.0 {
+                        curve.ease(t / duration)
+                    } else {
+                        1.0
+                    }
+                })
+            }
+            AnimationModel::Spring { .. } => {
+                let spring = self.spring().unwrap_or_default();
+                let settle_time = spring.settle_time(spring.initial_velocity);
+                sample(settle_time, |t| spring.position(t, spring.initial_velocity))
+            }
+        }
+    }
+
+    /// The spring this model describes, if it is one.
+    pub fn spring(&self) -> Option<Spring> {
+        match *self {
+            AnimationModel::Spring {
+                stiffness,
+                damping,
+                mass,
+                initial_velocity,
+            } => Some(Spring {
+                stiffness,
+                damping,
+                mass,
+                initial_velocity,
+            }),
+            AnimationModel::Bezier { .. } => None,
+        }
+    }
+
+    /// Whether the model can run: a spring needs positive stiffness, damping
+    /// and mass to ever settle, and every number has to be finite.
+    pub fn check(&self) -> Result<(), String> {
+        match *self {
+            AnimationModel::Bezier { duration, .. } if !duration.is_finite() => {
+                Err(format!("duration must be finite, not {}", duration))
+            }
+            AnimationModel::Bezier { .. } => Ok(()),
+            AnimationModel::Spring {
+                stiffness,
+                damping,
+                mass,
+                initial_velocity,
+            } => {
+                for (name, value) in [
+                    ("stiffness", stiffness),
+                    ("damping", damping),
+                    ("mass", mass),
+                ] {
+                    if !(value.is_finite() && value > 0.0) {
+                        return Err(format!("{} must be positive, not {}", name, value));
+                    }
+                }
+                if !initial_velocity.is_finite() {
+                    return Err(format!(
+                        "initial_velocity must be finite, not {}",
+                        initial_velocity
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The curve for config's `points`, or CSS `ease` if they aren't four numbers.
+pub fn bezier_or_ease(points: &[f64]) -> CubicBezier {
+    CubicBezier::from_points(points).unwrap_or_else(|| CubicBezier::new(0.25, 0.1, 0.25, 1.0))
+}
+
+// Samples `progress` over `settle_time`, ending exactly at the target
+fn sample(settle_time: f64, progress: impl Fn(f64) -> f64) -> AnimationCurve {
+    let intervals = (settle_time * SAMPLE_RATE).ceil().max(1.0) as usize;
+    let mut samples: Vec<f64> = (0..intervals)
+        .map(|i| progress(settle_time * i as f64 / intervals as f64))
+        .collect();
+    samples.push(1.0);
+    AnimationCurve {
+        settle_time,
+        samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::curve::MAX_SETTLE_TIME;
+    use crate::config::NotchConfig;
+
+    fn spring(stiffness: f64, damping: f64, mass: f64) -> AnimationModel {
+        AnimationModel::Spring {
+            stiffness,
+            damping,
+            mass,
+            initial_velocity: 0.0,
+        }
+    }
+
+    #[test]
+    fn check_rejects_springs_that_never_settle() {
+        assert!(spring(300.0, 30.0, 1.0).check().is_ok());
+        for bad in [
+            spring(300.0, 0.0, 1.0),
+            spring(0.0, 30.0, 1.0),
+            spring(300.0, 30.0, -1.0),
+            spring(f64::NAN, 30.0, 1.0),
+            spring(300.0, f64::INFINITY, 1.0),
+        ] {
+            assert!(bad.check().is_err(), "{bad:?}");
+        }
+        let kicked = AnimationModel::Spring {
+            stiffness: 300.0,
+            damping: 30.0,
+            mass: 1.0,
+            initial_velocity: f64::NAN,
+        };
+        assert!(kicked.check().is_err());
+    }
+
+    #[test]
+    fn check_rejects_endless_beziers() {
+        let bezier = |duration| AnimationModel::Bezier {
+            duration,
+            points: vec![0.25, 0.1, 0.25, 1.0],
+        };
+        assert!(bezier(0.35).check().is_ok());
+        assert!(bezier(f64::INFINITY).check().is_err());
+        assert!(bezier(f64::NAN).check().is_err());
+    }
+
+    #[test]
+    fn validate_replaces_only_the_bad_model() {
+        let mut config = NotchConfig::default().animation;
+        config.expand.value = spring(300.0, 0.0, 1.0);
+        config.collapse.value = spring(200.0, 25.0, 1.0);
+        config.validate();
+        assert_eq!(
+            config.expand.value,
+            NotchConfig::default().animation.expand.value
+        );
+        assert_eq!(config.collapse.value, spring(200.0, 25.0, 1.0));
+    }
+
+    #[test]
+    fn barely_damped_springs_solve_to_a_bounded_curve() {
+        let curve = spring(300.0, 1e-6, 1.0).solve();
+        assert_eq!(curve.settle_time, MAX_SETTLE_TIME);
+        assert_eq!(
+            curve.samples.len(),
+            (MAX_SETTLE_TIME * SAMPLE_RATE) as usize + 1
+        );
+        assert_eq!(curve.samples.last(), Some(&1.0));
+    }
+
+    #[test]
+    fn bezier_samples_span_the_duration() {
+        let curve = AnimationModel::Bezier {
+            duration: 0.5,
+            points: vec![0.0, 0.0, 1.0, 1.0],
+        }
+        .solve();
+        assert_eq!(curve.settle_time, 0.5);
+        assert_eq!(curve.samples.len(), 61);
+        assert_eq!(curve.samples[0], 0.0);
+        assert!((curve.samples[30] - 0.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,250 @@
+use super::{solver, CubicBezier, Spring};
+use crate::config::{AnimationConfig, AnimationModel};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    /// `duration` is for the timeline's whole span (0–1 unless set); shorter
+    /// trips, like reversing halfway, take proportionally less.
+    Bezier {
+        curve: CubicBezier,
+        duration: Duration,
+    },
+    /// Carries the current velocity into the new trip, so reversing is smooth.
+    Spring(Spring),
+}
+
+impl Easing {
+    /// The configured expand or collapse animation.
+    pub fn from_config(config: &AnimationConfig, expanding: bool) -> Self {
+        let model = if expanding {
+            &config.expand
+        } else {
+            &config.collapse
+        };
+        Self::from_model(&model.value)
+    }
+
+    pub fn from_model(model: &AnimationModel) -> Self {
+        match model {
+            AnimationModel::Bezier { duration, points } => Easing::Bezier {
+                curve: solver::bezier_or_ease(points),
+                duration: Duration::from_secs_f64(duration.max(0.0)),
+            },
+            AnimationModel::Spring { .. } => Easing::Spring(model.spring().unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    from: f64,
+    to: f64,
+    start: Instant,
+    length: Duration,
+    easing: Easing,
+    // In units of the segment's distance per second
+    velocity: f64,
+}
+
+impl Segment {
+    fn value(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.start);
+        if elapsed >= self.length {
+            return self.to;
+        }
+        let eased = match self.easing {
+            Easing::Bezier { curve, .. } => {
+                curve.ease(elapsed.as_secs_f64() / self.length.as_secs_f64())
+            }
+            Easing::Spring(spring) => spring.position(elapsed.as_secs_f64(), self.velocity),
+        };
+        self.from + (self.to - self.from) * eased
+    }
+}
+
+/// A value, normally progress from 0 (closed) to 1 (expanded), animated over
+/// time. Retargeting mid-flight starts the new trip from wherever the value is,
+/// so animations can be interrupted or reversed at any point. Every method takes
+/// the current time so it can be driven by a fake clock.
+#[derive(Clone, Debug)]
+pub struct Timeline {
+    rest: f64,
+    span: f64,
+    segment: Option<Segment>,
+}
+
+impl Timeline {
+    /// At rest at `value`.
+    pub fn at(value: f64) -> Self {
+        Self {
+            rest: value,
+            span: 1.0,
+            segment: None,
+        }
+    }
+
+    /// The distance a bezier trip takes its full duration to cover.
+    pub fn set_span(&mut self, span: f64) {
+        self.span = span.abs().max(f64::EPSILON);
+    }
+
+    pub fn value(&self, now: Instant) -> f64 {
+        match &self.segment {
+            Some(segment) => segment.value(now),
+            None => self.rest,
+        }
+    }
+
+    /// Rate of change per second.
+    pub fn velocity(&self, now: Instant) -> f64 {
+        const STEP: Duration = Duration::from_millis(1);
+        let Some(segment) = &self.segment else {
+            return 0.0;
+        };
+        let (before, after) = match now.checked_sub(STEP) {
+            Some(before) if before >= segment.start => (before, now),
+            _ => (now, now + STEP),
+        };
+        (segment.value(after) - segment.value(before)) / STEP.as_secs_f64()
+    }
+
+    /// Where the value is heading, or resting.
+    pub fn target(&self) -> f64 {
+        self.segment.map_or(self.rest, |segment| segment.to)
+    }
+
+    pub fn is_animating(&self, now: Instant) -> bool {
+        self.segment
+            .is_some_and(|segment| now.saturating_duration_since(segment.start) < segment.length)
+    }
+
+    /// Heads for `target` from the current value.
+    pub fn animate_to(&mut self, now: Instant, target: f64, easing: Easing) {
+        let from = self.value(now);
+        let distance = target - from;
+        if distance.abs() < 1e-6 * self.span {
+            self.jump_to(target);
+            return;
+        }
+        let velocity = match easing {
+            // Starting from rest, springs get their configured kick
+            Easing::Spring(spring) if !self.is_animating(now) => spring.initial_velocity,
+            _ => self.velocity(now) / distance,
+        };
+        let length = match easing {
+            Easing::Bezier { duration, .. } => {
+                duration.mul_f64((distance.abs() / self.span).min(1.0))
+            }
+            Easing::Spring(spring) => Duration::from_secs_f64(spring.settle_time(velocity)),
+        };
+        self.rest = target;
+        self.segment = Some(Segment {
+            from,
+            to: target,
+            start: now,
+            length,
+            easing,
+            velocity,
+        });
+    }
+
+    /// Stops any animation and rests at `value`.
+    pub fn jump_to(&mut self, value: f64) {
+        self.rest = value;
+        self.segment = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    fn linear(seconds: u64) -> Easing {
+        Easing::Bezier {
+            curve: CubicBezier::new(0.0, 0.0, 1.0, 1.0),
+            duration: Duration::from_secs(seconds),
+        }
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn bezier_runs_for_its_duration() {
+        let start = Instant::now();
+        let mut timeline = Timeline::at(0.0);
+        timeline.animate_to(start, 1.0, linear(1));
+        assert_near(timeline.value(ms(start, 250)), 0.25);
+        assert!(timeline.is_animating(ms(start, 999)));
+        assert!(!timeline.is_animating(ms(start, 1000)));
+        assert_eq!(timeline.value(ms(start, 1500)), 1.0);
+        assert_eq!(timeline.target(), 1.0);
+    }
+
+    #[test]
+    fn bezier_reverses_from_where_it_is() {
+        let start = Instant::now();
+        let mut timeline = Timeline::at(0.0);
+        timeline.animate_to(start, 1.0, linear(1));
+        timeline.animate_to(ms(start, 500), 0.0, linear(1));
+
+        // No jump, and half the distance takes half the duration
+        assert_near(timeline.value(ms(start, 500)), 0.5);
+        assert_near(timeline.value(ms(start, 750)), 0.25);
+        assert!(timeline.is_animating(ms(start, 999)));
+        assert!(!timeline.is_animating(ms(start, 1000)));
+        assert_eq!(timeline.value(ms(start, 1000)), 0.0);
+        assert_eq!(timeline.target(), 0.0);
+    }
+
+    #[test]
+    fn span_scales_bezier_durations() {
+        let start = Instant::now();
+        let mut timeline = Timeline::at(100.0);
+        timeline.set_span(400.0);
+        timeline.animate_to(start, 300.0, linear(1));
+        assert!(!timeline.is_animating(ms(start, 500)));
+        assert_eq!(timeline.value(ms(start, 500)), 300.0);
+    }
+
+    #[test]
+    fn spring_reverses_smoothly() {
+        let start = Instant::now();
+        let spring = Easing::Spring(Spring::default());
+        let mut timeline = Timeline::at(0.0);
+        timeline.animate_to(start, 1.0, spring);
+
+        let turn = ms(start, 60);
+        let (value, velocity) = (timeline.value(turn), timeline.velocity(turn));
+        assert!(velocity > 1.0);
+        timeline.animate_to(turn, 0.0, spring);
+
+        // Carries on from the same place at the same speed, then heads back
+        assert_near(timeline.value(turn), value);
+        let after = timeline.velocity(turn);
+        assert!(
+            (after - velocity).abs() < velocity * 0.05,
+            "{after} vs {velocity}"
+        );
+        assert!(timeline.value(ms(start, 200)) < value);
+        let settle = Duration::from_secs_f64(Spring::default().settle_time(0.0) * 2.0);
+        assert!((timeline.value(turn + settle)).abs() < 0.01);
+    }
+
+    #[test]
+    fn jump_to_stops_the_animation() {
+        let start = Instant::now();
+        let mut timeline = Timeline::at(0.0);
+        timeline.animate_to(start, 1.0, linear(1));
+        timeline.jump_to(0.4);
+        assert!(!timeline.is_animating(ms(start, 100)));
+        assert_eq!(timeline.value(ms(start, 100)), 0.4);
+        assert_eq!(timeline.velocity(ms(start, 100)), 0.0);
+    }
+}
@@ -0,0 +1,304 @@
+pub mod source;
+pub mod spectrum;
+
+use crate::config;
+use source::AudioSource;
+use spectrum::SpectrumAnalyzer;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SpectrumFrame {
+    pub bands: Vec<f32>,
+}
+
+// How long a capture that ended on its own (the capture tool is missing or
+// died) waits before another one starts
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Runs the spectrum pipeline only while the capsule is expanded and media is playing.
+#[derive(Default)]
+pub struct SpectrumState {
+    expanded: AtomicBool,
+    playing: AtomicBool,
+    worker: Mutex<Option<Worker>>,
+}
+
+/// A running capture: `stop` asks it to end, and it sets `finished` once its
+/// thread is done, whether asked to or not.
+struct Worker {
+    stop: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>,
+    started: Instant,
+}
+
+impl SpectrumState {
+    pub fn set_expanded(&self, app: &AppHandle, expanded: bool) {
+        self.expanded.store(expanded, Ordering::Relaxed);
+        self.reconcile(|| start(app.clone()));
+    }
+
+    pub fn set_playing(&self, app: &AppHandle, playing: bool) {
+        self.playing.store(playing, Ordering::Relaxed);
+        self.reconcile(|| start(app.clone()));
+    }
+
+    fn reconcile(&self, start: impl FnOnce() -> Option<Worker>) {
+        let wanted = self.expanded.load(Ordering::Relaxed) && self.playing.load(Ordering::Relaxed);
+        let Ok(mut worker) = self.worker.lock() else {
+            return;
+        };
+
+        match (wanted, worker.as_ref()) {
+            (true, None) => *worker = start(),
+            (true, Some(current))
+                if current.finished.load(Ordering::Relaxed)
+                    && current.started.elapsed() >= RESTART_DELAY =>
+            {
+                *worker = start()
+            }
+            (false, Some(current)) => {
+                current.stop.store(true, Ordering::Relaxed);
+                *worker = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn start(app: AppHandle) -> Option<Worker> {
+    let cfg = config::NotchConfig::get().audio.clone();
+    if cfg.source.value == "off" {
+        return None;
+    }
+
+    let worker = Worker {
+        stop: Arc::new(AtomicBool::new(false)),
+        finished: Arc::new(AtomicBool::new(false)),
+        started: Instant::now(),
+    };
+    let (stop, finished) = (worker.stop.clone(), worker.finished.clone());
+    thread::spawn(move || {
+        capture(&app, &cfg, &stop);
+        finished.store(true, Ordering::Relaxed);
+    });
+
+    Some(worker)
+}
+
+fn capture(app: &AppHandle, cfg: &config::AudioConfig, stop: &AtomicBool) {
+    let source = match source::from_config(&cfg.source.value, &cfg.device.value) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Audio spectrum source unavailable: {}", e);
+            return;
+        }
+    };
+    let analyzer = SpectrumAnalyzer::new(
+        source.sample_rate(),
+        cfg.fft_size.value,
+        cfg.bands.value,
+        cfg.min_frequency.value,
+        cfg.max_frequency.value,
+        cfg.smoothing.value,
+    );
+    let frame_interval = Duration::from_secs_f64(1.0 / cfg.max_fps.value.max(1) as f64);
+
+    pump(source, analyzer, frame_interval, stop, |bands| {
+        let _ = app.emit("audio-spectrum", SpectrumFrame { bands: bands.to_vec() });
+    });
+
+    // Let the waveform fall back to its idle animation.
+    let _ = app.emit("audio-spectrum", SpectrumFrame { bands: Vec::new() });
+}
+
+/// Reads `source` continuously so capture never backs up, and hands a band frame
+/// to `emit` at most once per `frame_interval`. Returns when the source ends or `stop` is set.
+pub fn pump(
+    mut source: Box<dyn AudioSource>,
+    mut analyzer: SpectrumAnalyzer,
+    frame_interval: Duration,
+    stop: &AtomicBool,
+    mut emit: impl FnMut(&[f32]),
+) {
+    let size = analyzer.fft_size();
+    let mut history = vec![0.0f32; size];
+    let mut chunk = vec![0.0f32; (size / 4).max(1)];
+    let mut last_frame = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        let read = match source.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => {
+                eprintln!("Audio spectrum read failed: {}", e);
+                break;
+            }
+        };
+
+        history.copy_within(read.., 0);
+        history[size - read..].copy_from_slice(&chunk[..read]);
+
+        if last_frame.elapsed() >= frame_interval {
+            last_frame = Instant::now();
+            emit(analyzer.process(&history));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::source::WavSource;
+    use super::*;
+    use std::f32::consts::PI;
+
+    const RATE: u32 = 44_100;
+
+    // Writes one second of `sample(i)` as a 16-bit stereo WAV and opens it
+    fn wav(name: &str, sample: impl Fn(u32) -> f32) -> WavSource {
+        let path = std::env::temp_dir().join(format!("notch-{}-{}.wav", name, std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..RATE {
+            let value = (sample(i) * i16::MAX as f32) as i16;
+            writer.write_sample(value).unwrap();
+            writer.write_sample(value).unwrap();
+        }
+        writer.finalize().unwrap();
+        let source = WavSource::open(&path);
+        let _ = std::fs::remove_file(&path);
+        source.unwrap()
+    }
+
+    fn frames(source: WavSource, stop: bool) -> Vec<Vec<f32>> {
+        let analyzer = SpectrumAnalyzer::new(RATE, 2048, 16, 40.0, 16_000.0, 0.0);
+        let mut frames = Vec::new();
+        pump(
+            Box::new(source),
+            analyzer,
+            Duration::ZERO,
+            &AtomicBool::new(stop),
+            |bands| frames.push(bands.to_vec()),
+        );
+        frames
+    }
+
+    // The last frame for a half-amplitude sine at `hz`
+    fn sine(hz: f32) -> Vec<f32> {
+        let source = wav(&format!("sine-{}", hz), |i| {
+            0.5 * (2.0 * PI * hz * i as f32 / RATE as f32).sin()
+        });
+        frames(source, false).pop().expect("no frames")
+    }
+
+    fn loudest(bands: &[f32]) -> usize {
+        (0..bands.len())
+            .max_by(|&a, &b| bands[a].total_cmp(&bands[b]))
+            .unwrap()
+    }
+
+    #[test]
+    fn sine_peaks_in_one_band() {
+        let bands = sine(1000.0);
+        let peak = loudest(&bands);
+        // -6 dB maps to 0.9, less whatever falls between bins
+        assert!(bands[peak] > 0.8, "{:?}", bands);
+        for (i, level) in bands.iter().enumerate() {
+            if i.abs_diff(peak) >= 3 {
+                assert!(*level < 0.3, "band {} at {} in {:?}", i, level, bands);
+            }
+        }
+    }
+
+    #[test]
+    fn higher_tones_peak_in_higher_bands() {
+        let peaks: Vec<usize> = [100.0, 1000.0, 8000.0]
+            .into_iter()
+            .map(|hz| loudest(&sine(hz)))
+            .collect();
+        assert!(peaks[0] < peaks[1] && peaks[1] < peaks[2], "{:?}", peaks);
+    }
+
+    #[test]
+    fn silence_stays_at_zero() {
+        let frames = frames(wav("silence", |_| 0.0), false);
+        assert!(!frames.is_empty());
+        assert!(frames.iter().flatten().all(|level| *level == 0.0));
+    }
+
+    #[test]
+    fn pump_returns_once_stopped() {
+        assert!(frames(wav("stopped", |_| 0.0), true).is_empty());
+    }
+
+    #[test]
+    fn bands_above_nyquist_squeeze_under_it() {
+        // 11 kHz can't be resolved at 8 kHz
+        let mut analyzer = SpectrumAnalyzer::new(8_000, 2048, 5, 11_000.0, 12_000.0, 0.0);
+        assert_eq!(analyzer.process(&[0.0; 2048]).len(), 5);
+    }
+
+    fn worker(finished: bool, age: Duration) -> Worker {
+        Worker {
+            stop: Arc::new(AtomicBool::new(false)),
+            finished: Arc::new(AtomicBool::new(finished)),
+            started: Instant::now() - age,
+        }
+    }
+
+    fn wanted(wanted: bool) -> SpectrumState {
+        let state = SpectrumState::default();
+        state.expanded.store(wanted, Ordering::Relaxed);
+        state.playing.store(wanted, Ordering::Relaxed);
+        state
+    }
+
+    fn running(state: &SpectrumState) -> Option<(bool, bool)> {
+        let worker = state.worker.lock().unwrap();
+        worker.as_ref().map(|worker| {
+            (
+                worker.stop.load(Ordering::Relaxed),
+                worker.finished.load(Ordering::Relaxed),
+            )
+        })
+    }
+
+    #[test]
+    fn reconcile_starts_and_stops_the_worker() {
+        let state = wanted(true);
+        state.reconcile(|| Some(worker(false, Duration::ZERO)));
+        assert_eq!(running(&state), Some((false, false)));
+
+        // Already running
+        state.reconcile(|| panic!("started twice"));
+
+        let stop = state.worker.lock().unwrap().as_ref().unwrap().stop.clone();
+        state.playing.store(false, Ordering::Relaxed);
+        state.reconcile(|| panic!("started while paused"));
+        assert_eq!(running(&state), None);
+        assert!(stop.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn reconcile_restarts_a_worker_that_ended() {
+        let state = wanted(true);
+        *state.worker.lock().unwrap() = Some(worker(true, Duration::ZERO));
+        // Too soon after the last attempt, e.g. parec is missing
+        state.reconcile(|| panic!("restarted without waiting"));
+        assert_eq!(running(&state), Some((false, true)));
+
+        *state.worker.lock().unwrap() = Some(worker(true, RESTART_DELAY));
+        state.reconcile(|| Some(worker(false, Duration::ZERO)));
+        assert_eq!(running(&state), Some((false, false)));
+    }
+}
@@ -0,0 +1,209 @@
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Sample rate requested from capture tools; analysis adapts to whatever a source reports.
+pub const CAPTURE_RATE: u32 = 44_100;
+
+/// A pull-based stream of mono PCM samples in `-1.0..=1.0`.
+pub trait AudioSource: Send {
+    fn sample_rate(&self) -> u32;
+
+    /// Fills `buf` with the next samples and returns how many were written.
+    /// `Ok(0)` means the stream has ended.
+    fn read(&mut self, buf: &mut [f32]) -> io::Result<usize>;
+}
+
+/// Builds a source from the `audio.source` config value:
+/// `"pulse"`, `"pipewire"`, or `"wav:<path>"`.
+pub fn from_config(source: &str, device: &str) -> io::Result<Box<dyn AudioSource>> {
+    match source {
+        "pulse" => Ok(Box::new(MonitorSource::pulse(device)?)),
+        "pipewire" => Ok(Box::new(MonitorSource::pipewire(device)?)),
+        other => match other.strip_prefix("wav:") {
+            Some(path) => Ok(Box::new(WavSource::open(path)?.realtime())),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown audio source '{other}'"),
+            )),
+        },
+    }
+}
+
+/// Captures the system mix from a PulseAudio/PipeWire monitor by reading raw
+/// s16le output from `parec` or `pw-record`.
+pub struct MonitorSource {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+    bytes: Vec<u8>,
+}
+
+impl MonitorSource {
+    /// Records the monitor of `device` (empty = default sink) through `parec`.
+    /// Works on PipeWire too via `pipewire-pulse`.
+    pub fn pulse(device: &str) -> io::Result<Self> {
+        let device = if device.is_empty() { "@DEFAULT_MONITOR@" } else { device };
+        let mut cmd = Command::new("parec");
+        cmd.arg("--raw")
+            .arg("--format=s16le")
+            .arg("--channels=1")
+            .arg(format!("--rate={CAPTURE_RATE}"))
+            .arg("--latency-msec=20")
+            .arg(format!("--device={device}"));
+        Self::spawn(cmd)
+    }
+
+    /// Records the output of `device` (empty = default sink) through `pw-record`.
+    pub fn pipewire(device: &str) -> io::Result<Self> {
+        let mut cmd = Command::new("pw-record");
+        cmd.arg("--format=s16")
+            .arg("--channels=1")
+            .arg(format!("--rate={CAPTURE_RATE}"))
+            .arg("-P")
+            .arg("{ stream.capture.sink = true }");
+        if !device.is_empty() {
+            cmd.arg(format!("--target={device}"));
+        }
+        cmd.arg("-");
+        Self::spawn(cmd)
+    }
+
+    fn spawn(mut cmd: Command) -> io::Result<Self> {
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "capture stdout missing"))?;
+        Ok(Self {
+            child,
+            stdout: BufReader::new(stdout),
+            bytes: Vec::new(),
+        })
+    }
+}
+
+impl AudioSource for MonitorSource {
+    fn sample_rate(&self) -> u32 {
+        CAPTURE_RATE
+    }
+
+    fn read(&mut self, buf: &mut [f32]) -> io::Result<usize> {
+        self.bytes.resize(buf.len() * 2, 0);
+        let mut filled = 0;
+        // Blocks until at least one whole sample is available.
+        while filled < 2 {
+            match self.stdout.read(&mut self.bytes[filled..])? {
+                0 => return Ok(0),
+                n => filled += n,
+            }
+        }
+        if filled % 2 == 1 {
+            self.stdout.read_exact(&mut self.bytes[filled..filled + 1])?;
+            filled += 1;
+        }
+
+        let count = filled / 2;
+        for (out, pair) in buf.iter_mut().zip(self.bytes[..filled].chunks_exact(2)) {
+            *out = i16::from_le_bytes([pair[0], pair[1]]) as f32 / i16::MAX as f32;
+        }
+        Ok(count)
+    }
+}
+
+impl Drop for MonitorSource {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Plays back a WAV file, downmixed to mono. Reads as fast as requested unless
+/// [`realtime`](Self::realtime) is enabled, which paces and loops it like a live stream.
+pub struct WavSource {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    position: usize,
+    realtime: Option<Instant>,
+}
+
+impl WavSource {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = hound::WavReader::open(path).map_err(invalid_data)?;
+        let spec = reader.spec();
+        let channels = spec.channels.max(1) as usize;
+
+        let interleaved: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader
+                .into_samples::<f32>()
+                .collect::<Result<_, _>>()
+                .map_err(invalid_data)?,
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|s| s.map(|v| v as f32 / scale))
+                    .collect::<Result<_, _>>()
+                    .map_err(invalid_data)?
+            }
+        };
+
+        let samples = interleaved
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect();
+
+        Ok(Self {
+            samples,
+            sample_rate: spec.sample_rate,
+            position: 0,
+            realtime: None,
+        })
+    }
+
+    pub fn realtime(mut self) -> Self {
+        self.realtime = Some(Instant::now());
+        self
+    }
+}
+
+impl AudioSource for WavSource {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn read(&mut self, buf: &mut [f32]) -> io::Result<usize> {
+        if self.samples.is_empty() {
+            return Ok(0);
+        }
+
+        if let Some(started) = self.realtime {
+            if self.position >= self.samples.len() {
+                self.position = 0;
+                self.realtime = Some(Instant::now());
+                return self.read(buf);
+            }
+            let due = Duration::from_secs_f64(
+                (self.position + buf.len()) as f64 / self.sample_rate as f64,
+            );
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+
+        let remaining = &self.samples[self.position.min(self.samples.len())..];
+        let count = remaining.len().min(buf.len());
+        buf[..count].copy_from_slice(&remaining[..count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+fn invalid_data(err: hound::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,114 @@
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+// Levels below this are drawn as silence; the range above maps linearly to 0..1.
+const DB_FLOOR: f32 = -60.0;
+
+/// Turns blocks of mono PCM into `N` log-spaced, smoothed band levels in `0.0..=1.0`.
+pub struct SpectrumAnalyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    window_gain: f32,
+    bands: Vec<(usize, usize)>,
+    smoothing: f32,
+    buffer: Vec<Complex<f32>>,
+    levels: Vec<f32>,
+}
+
+impl SpectrumAnalyzer {
+    pub fn new(
+        sample_rate: u32,
+        fft_size: usize,
+        band_count: usize,
+        min_hz: f64,
+        max_hz: f64,
+        smoothing: f64,
+    ) -> Self {
+        let fft_size = fft_size.max(64).next_power_of_two();
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(fft_size);
+
+        // Hann window; its coherent gain is used to normalise magnitudes.
+        let window: Vec<f32> = (0..fft_size)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / (fft_size - 1) as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+        let window_gain = window.iter().sum::<f32>() / 2.0;
+
+        let bands = log_bands(sample_rate, fft_size, band_count.max(1), min_hz, max_hz);
+        let band_count = bands.len();
+
+        Self {
+            fft,
+            window,
+            window_gain,
+            bands,
+            smoothing: smoothing.clamp(0.0, 0.99) as f32,
+            buffer: vec![Complex::new(0.0, 0.0); fft_size],
+            levels: vec![0.0; band_count],
+        }
+    }
+
+    /// Number of samples expected by [`process`](Self::process).
+    pub fn fft_size(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Analyses the most recent `fft_size` samples and returns the smoothed band levels.
+    /// Shorter input is zero-padded at the front.
+    pub fn process(&mut self, samples: &[f32]) -> &[f32] {
+        let size = self.window.len();
+        let tail = &samples[samples.len().saturating_sub(size)..];
+        let pad = size - tail.len();
+
+        for (i, slot) in self.buffer.iter_mut().enumerate() {
+            let sample = if i < pad { 0.0 } else { tail[i - pad] };
+            *slot = Complex::new(sample * self.window[i], 0.0);
+        }
+        self.fft.process(&mut self.buffer);
+
+        for (level, &(lo, hi)) in self.levels.iter_mut().zip(&self.bands) {
+            let peak = self.buffer[lo..hi]
+                .iter()
+                .map(|c| c.norm())
+                .fold(0.0f32, f32::max)
+                / self.window_gain;
+            let db = 20.0 * peak.max(1e-9).log10();
+            let target = ((db - DB_FLOOR) / -DB_FLOOR).clamp(0.0, 1.0);
+            *level = self.smoothing * *level + (1.0 - self.smoothing) * target;
+        }
+
+        &self.levels
+    }
+}
+
+/// Splits `min_hz..max_hz` into `count` logarithmically spaced FFT bin ranges.
+/// Every band covers at least one bin, so narrow low bands never come out empty.
+fn log_bands(
+    sample_rate: u32,
+    fft_size: usize,
+    count: usize,
+    min_hz: f64,
+    max_hz: f64,
+) -> Vec<(usize, usize)> {
+    let nyquist_bin = fft_size / 2;
+    let hz_per_bin = sample_rate as f64 / fft_size as f64;
+    let nyquist = sample_rate as f64 / 2.0;
+    // Config validation keeps these in range for live capture, but a WAV can
+    // have any rate, so squeeze the range under Nyquist rather than clamp
+    let min_hz = min_hz.max(hz_per_bin).min(nyquist / 1.01);
+    let max_hz = max_hz.min(nyquist).max(min_hz * 1.01);
+    let ratio = max_hz / min_hz;
+
+    let mut bands = Vec::with_capacity(count);
+    let mut next_lo = (min_hz / hz_per_bin).floor() as usize;
+    for i in 0..count {
+        let edge = min_hz * ratio.powf((i + 1) as f64 / count as f64);
+        let lo = next_lo.min(nyquist_bin - 1);
+        let hi = ((edge / hz_per_bin).floor() as usize).clamp(lo + 1, nyquist_bin);
+        bands.push((lo, hi));
+        next_lo = hi;
+    }
+    bands
+}
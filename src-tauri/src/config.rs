@@ -1,22 +1,157 @@
-use serde::{Deserialize, Serialize};
+use crate::animation::{SolvedAnimations, Spring};
+use crate::state::NotchView;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
 static CONFIG: OnceLock<NotchConfig> = OnceLock::new();
 
+// Upper bounds for the audio settings; past these the analysis costs more
+// than the waveform can show.
+const MAX_AUDIO_BANDS: usize = 64;
+const MAX_FFT_SIZE: usize = 32_768;
+const MAX_SPECTRUM_FPS: u32 = 240;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotchConfig {
+    #[serde(deserialize_with = "animation_or_default")]
     pub animation: AnimationConfig,
     pub dimensions: DimensionsConfig,
     pub hover: HoverConfig,
     pub window: WindowConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    #[serde(default)]
+    pub media: MediaConfig,
+    #[serde(default)]
+    pub activation: ActivationConfig,
+    #[serde(default)]
+    pub pin: PinConfig,
+    #[serde(default)]
+    pub peek: PeekConfig,
+    #[serde(default)]
+    pub synthetic_notch: SyntheticNotchConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "AnimationFile")]
 pub struct AnimationConfig {
-    pub expand_duration: ConfigValue<f64>,
-    pub collapse_duration: ConfigValue<f64>,
-    pub expand_timing: ConfigValue<Vec<f64>>,
-    pub collapse_timing: ConfigValue<Vec<f64>>,
+    pub expand: ConfigValue<AnimationModel>,
+    pub collapse: ConfigValue<AnimationModel>,
+    /// `expand` and `collapse` solved when the config loads, for consumers
+    /// that play samples instead of running the models. Never read from the file.
+    #[serde(default, skip_deserializing)]
+    pub solved: SolvedAnimations,
+}
+
+/// `animation` as the file has it: the models, or the keys they replaced in
+/// older configs, i.e. a duration and timing curve per direction plus a
+/// `spring` switch. A model wins over the old keys; without either, the
+/// default model is used.
+#[derive(Deserialize)]
+struct AnimationFile {
+    expand: Option<ConfigValue<AnimationModel>>,
+    collapse: Option<ConfigValue<AnimationModel>>,
+    expand_duration: Option<ConfigValue<f64>>,
+    collapse_duration: Option<ConfigValue<f64>>,
+    expand_timing: Option<ConfigValue<Vec<f64>>>,
+    collapse_timing: Option<ConfigValue<Vec<f64>>>,
+    spring: Option<ConfigValue<bool>>,
+}
+
+impl From<AnimationFile> for AnimationConfig {
+    fn from(file: AnimationFile) -> Self {
+        let defaults = NotchConfig::default().animation;
+        let spring = file.spring.is_some_and(|spring| spring.value);
+        // The durations and curves the old keys defaulted to
+        let expand = file.expand.unwrap_or_else(|| ConfigValue {
+            value: legacy_model(
+                spring,
+                file.expand_duration,
+                file.expand_timing,
+                0.5,
+                &[0.16, 1.0, 0.3, 1.0],
+            )
+            .unwrap_or(defaults.expand.value),
+            description: defaults.expand.description,
+        });
+        let collapse = file.collapse.unwrap_or_else(|| ConfigValue {
+            value: legacy_model(
+                spring,
+                file.collapse_duration,
+                file.collapse_timing,
+                0.35,
+                &[0.25, 0.1, 0.25, 1.0],
+            )
+            .unwrap_or(defaults.collapse.value),
+            description: defaults.collapse.description,
+        });
+        Self {
+            expand,
+            collapse,
+            solved: SolvedAnimations::default(),
+        }
+    }
+}
+
+// `spring: true` replaced the curves with the default spring; otherwise a
+// duration or timing curve makes a bezier, filling in the other one
+fn legacy_model(
+    spring: bool,
+    duration: Option<ConfigValue<f64>>,
+    timing: Option<ConfigValue<Vec<f64>>>,
+    default_duration: f64,
+    default_points: &[f64],
+) -> Option<AnimationModel> {
+    if spring {
+        let Spring {
+            stiffness,
+            damping,
+            mass,
+            initial_velocity,
+        } = Spring::default();
+        return Some(AnimationModel::Spring {
+            stiffness,
+            damping,
+            mass,
+            initial_velocity,
+        });
+    }
+    if duration.is_none() && timing.is_none() {
+        return None;
+    }
+    Some(AnimationModel::Bezier {
+        duration: duration.map_or(default_duration, |duration| duration.value),
+        points: timing.map_or_else(|| default_points.to_vec(), |timing| timing.value),
+    })
+}
+
+// A bad animation section shouldn't throw away the rest of the file
+fn animation_or_default<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AnimationConfig, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(AnimationConfig::deserialize(value).unwrap_or_else(|e| {
+        eprintln!("Invalid animation config, using defaults: {}", e);
+        NotchConfig::default().animation
+    }))
+}
+
+/// How the capsule moves between sizes, tagged by `model` in the JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum AnimationModel {
+    /// `duration` seconds along cubic bezier control points `[x1, y1, x2, y2]`.
+    Bezier { duration: f64, points: Vec<f64> },
+    /// A damped spring that runs until it settles. `initial_velocity` is in
+    /// units of the whole distance per second.
+    Spring {
+        stiffness: f64,
+        damping: f64,
+        mass: f64,
+        #[serde(default)]
+        initial_velocity: f64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +161,30 @@ pub struct DimensionsConfig {
     pub collapsed_height: ConfigValue<f64>,
     pub expanded_width: ConfigValue<f64>,
     pub expanded_height: ConfigValue<f64>,
+    #[serde(default = "default_peek_width")]
+    pub peek_width: ConfigValue<f64>,
+    #[serde(default = "default_peek_height")]
+    pub peek_height: ConfigValue<f64>,
+    #[serde(default = "default_view_sizes")]
+    pub views: ConfigValue<HashMap<NotchView, ViewSize>>,
+}
+
+impl DimensionsConfig {
+    /// The expanded size for `view`: its entry in `views`, or `expanded_width`
+    /// × `expanded_height` without one.
+    pub fn expanded_size(&self, view: NotchView) -> (f64, f64) {
+        self.views.value.get(&view).map_or(
+            (self.expanded_width.value, self.expanded_height.value),
+            |size| (size.width, size.height),
+        )
+    }
+}
+
+/// An expanded size in points for one tab.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ViewSize {
+    pub width: f64,
+    pub height: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,9 +193,53 @@ pub struct HoverConfig {
     pub collapsed_zone_height: ConfigValue<f64>,
     pub expanded_zone_width: ConfigValue<f64>,
     pub expanded_zone_height: ConfigValue<f64>,
+    #[serde(default = "default_peek_zone_width")]
+    pub peek_zone_width: ConfigValue<f64>,
+    #[serde(default = "default_peek_zone_height")]
+    pub peek_zone_height: ConfigValue<f64>,
     pub expand_delay_ms: ConfigValue<u64>,
     pub collapse_delay_ms: ConfigValue<u64>,
     pub poll_interval_ms: ConfigValue<u64>,
+    #[serde(default = "default_idle_poll_interval_ms")]
+    pub idle_poll_interval_ms: ConfigValue<u64>,
+    #[serde(default = "default_near_distance")]
+    pub near_distance: ConfigValue<f64>,
+    #[serde(default = "default_velocity_threshold")]
+    pub velocity_threshold: ConfigValue<f64>,
+    #[serde(default)]
+    pub zone: HoverZoneConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoverZoneConfig {
+    pub shape: ConfigValue<String>,
+    pub corner_radius: ConfigValue<f64>,
+    pub taper: ConfigValue<f64>,
+    pub exit_margin: ConfigValue<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivationConfig {
+    pub mode: ConfigValue<String>,
+    pub modifier: ConfigValue<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinConfig {
+    pub persist: ConfigValue<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeekConfig {
+    pub dismiss_ms: ConfigValue<u64>,
+}
+
+/// The notch assumed on displays without one, e.g. external monitors and Linux.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyntheticNotchConfig {
+    pub width: ConfigValue<f64>,
+    pub inset: ConfigValue<f64>,
+    pub scale: ConfigValue<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +247,38 @@ pub struct WindowConfig {
     pub level_offset: ConfigValue<i32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioConfig {
+    pub source: ConfigValue<String>,
+    pub device: ConfigValue<String>,
+    pub bands: ConfigValue<usize>,
+    pub fft_size: ConfigValue<usize>,
+    pub min_frequency: ConfigValue<f64>,
+    pub max_frequency: ConfigValue<f64>,
+    pub smoothing: ConfigValue<f64>,
+    pub max_fps: ConfigValue<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaConfig {
+    #[serde(default)]
+    pub sources: MediaSourcesConfig,
+    #[serde(default)]
+    pub mock: MockMediaConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaSourcesConfig {
+    pub allow: ConfigValue<Vec<String>>,
+    pub deny: ConfigValue<Vec<String>>,
+    pub min_duration: ConfigValue<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockMediaConfig {
+    pub script: ConfigValue<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigValue<T> {
     pub value: T,
@@ -75,34 +310,106 @@ impl NotchConfig {
 
     pub fn get() -> &'static NotchConfig {
         CONFIG.get_or_init(|| {
-            Self::load().unwrap_or_else(|e| {
+            let mut config = Self::load().unwrap_or_else(|e| {
                 eprintln!("Failed to load config, using defaults: {}", e);
                 Self::default()
-            })
+            });
+            config.validate();
+            config.animation.solved = SolvedAnimations::solve(&config.animation);
+            config
         })
     }
 }
 
+impl NotchConfig {
+    /// Replaces values that would stall or crash their consumers with the
+    /// defaults, logging each one.
+    pub fn validate(&mut self) {
+        self.animation.validate();
+        self.audio.validate();
+    }
+}
+
+impl AnimationConfig {
+    /// Swaps models that can't run (see [`AnimationModel::check`]) for the
+    /// defaults, so a bad spring can't stall or crash the animations.
+    pub fn validate(&mut self) {
+        let defaults = NotchConfig::default().animation;
+        for (name, model, default) in [
+            ("expand", &mut self.expand.value, defaults.expand.value),
+            ("collapse", &mut self.collapse.value, defaults.collapse.value),
+        ] {
+            if let Err(e) = model.check() {
+                eprintln!("Invalid animation.{}: {}; using the default", name, e);
+                *model = default;
+            }
+        }
+    }
+}
+
+impl AudioConfig {
+    /// Checks the band layout against what a capture at
+    /// [`CAPTURE_RATE`](crate::audio::source::CAPTURE_RATE) can resolve, and
+    /// the frame rate. The frequencies are replaced together, since each is
+    /// only valid relative to the other.
+    pub fn validate(&mut self) {
+        let defaults = AudioConfig::default();
+        let nyquist = crate::audio::source::CAPTURE_RATE as f64 / 2.0;
+        let (min, max) = (self.min_frequency.value, self.max_frequency.value);
+        if !(min.is_finite() && max.is_finite() && min > 0.0 && min * 1.01 <= max && max <= nyquist)
+        {
+            eprintln!(
+                "Invalid audio frequencies {}..{} Hz (need 0 < min < max <= {} Hz); using the defaults",
+                min, max, nyquist
+            );
+            self.min_frequency.value = defaults.min_frequency.value;
+            self.max_frequency.value = defaults.max_frequency.value;
+        }
+        if !(1..=MAX_AUDIO_BANDS).contains(&self.bands.value) {
+            eprintln!(
+                "Invalid audio.bands {} (need 1 to {}); using the default",
+                self.bands.value, MAX_AUDIO_BANDS
+            );
+            self.bands.value = defaults.bands.value;
+        }
+        if !(64..=MAX_FFT_SIZE).contains(&self.fft_size.value) {
+            eprintln!(
+                "Invalid audio.fft_size {} (need 64 to {}); using the default",
+                self.fft_size.value, MAX_FFT_SIZE
+            );
+            self.fft_size.value = defaults.fft_size.value;
+        }
+        if !(1..=MAX_SPECTRUM_FPS).contains(&self.max_fps.value) {
+            eprintln!(
+                "Invalid audio.max_fps {} (need 1 to {}); using the default",
+                self.max_fps.value, MAX_SPECTRUM_FPS
+            );
+            self.max_fps.value = defaults.max_fps.value;
+        }
+    }
+}
+
 impl Default for NotchConfig {
     fn default() -> Self {
         Self {
             animation: AnimationConfig {
-                expand_duration: ConfigValue {
-                    value: 0.50,
-                    description: "Duration in seconds for the expand animation".to_string(),
-                },
-                collapse_duration: ConfigValue {
-                    value: 0.35,
-                    description: "Duration in seconds for the collapse animation".to_string(),
+                expand: ConfigValue {
+                    value: AnimationModel::Spring {
+                        stiffness: 300.0,
+                        damping: 30.0,
+                        mass: 1.0,
+                        initial_velocity: 0.0,
+                    },
+                    description: "Expand animation".to_string(),
                 },
-                expand_timing: ConfigValue {
-                    value: vec![0.16, 1.0, 0.3, 1.0],
-                    description: "Cubic bezier control points for expand animation".to_string(),
-                },
-                collapse_timing: ConfigValue {
-                    value: vec![0.25, 0.1, 0.25, 1.0],
-                    description: "Cubic bezier control points for collapse animation".to_string(),
+                collapse: ConfigValue {
+                    value: AnimationModel::Bezier {
+                        duration: 0.35,
+                        points: vec![0.25, 0.1, 0.25, 1.0],
+                    },
+                    description: "Collapse animation".to_string(),
                 },
+                solved: SolvedAnimations::default(),
             },
             dimensions: DimensionsConfig {
                 corner_radius: ConfigValue {
@@ -125,6 +432,9 @@ impl Default for NotchConfig {
                     value: 200.0,
                     description: "Height when expanded".to_string(),
                 },
+                peek_width: default_peek_width(),
+                peek_height: default_peek_height(),
+                views: default_view_sizes(),
             },
             hover: HoverConfig {
                 collapsed_zone_width: ConfigValue {
@@ -143,6 +453,8 @@ impl Default for NotchConfig {
                     value: 200.0,
                     description: "Hover zone height when expanded".to_string(),
                 },
+                peek_zone_width: default_peek_zone_width(),
+                peek_zone_height: default_peek_zone_height(),
                 expand_delay_ms: ConfigValue {
                     value: 250,
                     description: "Milliseconds to wait before expanding when hovering over the notch area".to_string(),
@@ -155,6 +467,10 @@ impl Default for NotchConfig {
                     value: 50,
                     description: "Mouse polling interval in milliseconds".to_string(),
                 },
+                idle_poll_interval_ms: default_idle_poll_interval_ms(),
+                near_distance: default_near_distance(),
+                velocity_threshold: default_velocity_threshold(),
+                zone: HoverZoneConfig::default(),
             },
             window: WindowConfig {
                 level_offset: ConfigValue {
@@ -162,7 +478,344 @@ impl Default for NotchConfig {
                     description: "Window level offset above main menu".to_string(),
                 },
             },
+            audio: AudioConfig::default(),
+            media: MediaConfig::default(),
+            activation: ActivationConfig::default(),
+            pin: PinConfig::default(),
+            peek: PeekConfig::default(),
+            synthetic_notch: SyntheticNotchConfig::default(),
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            source: ConfigValue {
+                value: "pulse".to_string(),
+                description: "PCM source for the spectrum: pulse, pipewire, wav:<path>, or off".to_string(),
+            },
+            device: ConfigValue {
+                value: String::new(),
+                description: "Capture device or sink name (empty = default output monitor)".to_string(),
+            },
+            bands: ConfigValue {
+                value: 5,
+                description: "Number of log-spaced frequency bands".to_string(),
+            },
+            fft_size: ConfigValue {
+                value: 2048,
+                description: "FFT window size in samples (rounded up to a power of two)".to_string(),
+            },
+            min_frequency: ConfigValue {
+                value: 40.0,
+                description: "Lowest frequency in Hz covered by the first band".to_string(),
+            },
+            max_frequency: ConfigValue {
+                value: 12000.0,
+                description: "Highest frequency in Hz covered by the last band".to_string(),
+            },
+            smoothing: ConfigValue {
+                value: 0.6,
+                description: "Band smoothing between frames (0 = none, close to 1 = very smooth)".to_string(),
+            },
+            max_fps: ConfigValue {
+                value: 30,
+                description: "Maximum spectrum frames emitted per second".to_string(),
+            },
         }
     }
 }
 
+
+impl Default for MediaSourcesConfig {
+    fn default() -> Self {
+        Self {
+            allow: ConfigValue {
+                value: Vec::new(),
+                description: "Player patterns (identity or bus name, * and ? wildcards) allowed to show; empty allows all".to_string(),
+            },
+            deny: ConfigValue {
+                value: Vec::new(),
+                description: "Player patterns (identity or bus name, * and ? wildcards) that are never shown".to_string(),
+            },
+            min_duration: ConfigValue {
+                value: 0.0,
+                description: "Ignore tracks shorter than this many seconds (0 = off; unknown lengths always pass)".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for MockMediaConfig {
+    fn default() -> Self {
+        Self {
+            script: ConfigValue {
+                value: String::new(),
+                description: "Path to a mock player script that replaces the real media backend (empty = off)".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for HoverZoneConfig {
+    fn default() -> Self {
+        Self {
+            shape: ConfigValue {
+                value: "rectangle".to_string(),
+                description: "Hover zone shape: rectangle, rounded, trapezoid, or silhouette (the capsule outline)".to_string(),
+            },
+            corner_radius: ConfigValue {
+                value: 16.0,
+                description: "Bottom corner radius in points for the rounded shape".to_string(),
+            },
+            taper: ConfigValue {
+                value: 40.0,
+                description: "How far in points each side of the trapezoid narrows toward the bottom".to_string(),
+            },
+            exit_margin: ConfigValue {
+                value: 8.0,
+                description: "Extra points around the zone the pointer must cross before it counts as leaving".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for ActivationConfig {
+    fn default() -> Self {
+        Self {
+            mode: ConfigValue {
+                value: "hover".to_string(),
+                description: "How the capsule expands: hover, click, hover_with_modifier, or hybrid (hover or click)".to_string(),
+            },
+            modifier: ConfigValue {
+                value: "option".to_string(),
+                description: "Key to hold in hover_with_modifier mode: shift, control, option, or command".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for PinConfig {
+    fn default() -> Self {
+        Self {
+            persist: ConfigValue {
+                value: false,
+                description: "Remember whether the capsule is pinned open across restarts".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for PeekConfig {
+    fn default() -> Self {
+        Self {
+            dismiss_ms: ConfigValue {
+                value: 3000,
+                description: "Milliseconds the capsule stays at the peek stage before returning to compact".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for SyntheticNotchConfig {
+    fn default() -> Self {
+        Self {
+            width: ConfigValue {
+                value: 200.0,
+                description: "Width in points of the notch assumed on displays without one".to_string(),
+            },
+            inset: ConfigValue {
+                value: 0.0,
+                description: "Height in points of the assumed notch (0 = the menu bar or top panel height, or 32 without one)".to_string(),
+            },
+            scale: ConfigValue {
+                value: 0.0,
+                description: "Scale factor for the assumed notch's pixel sizes (0 = the display's)".to_string(),
+            },
+        }
+    }
+}
+
+fn default_peek_width() -> ConfigValue<f64> {
+    ConfigValue {
+        value: 520.0,
+        description: "Width in points at the peek stage, between collapsed and expanded".to_string(),
+    }
+}
+
+fn default_peek_height() -> ConfigValue<f64> {
+    ConfigValue {
+        value: 90.0,
+        description: "Height in points at the peek stage, between collapsed and expanded".to_string(),
+    }
+}
+
+fn default_view_sizes() -> ConfigValue<HashMap<NotchView, ViewSize>> {
+    ConfigValue {
+        value: HashMap::from([
+            (
+                NotchView::Archive,
+                ViewSize {
+                    width: 700.0,
+                    height: 320.0,
+                },
+            ),
+            (
+                NotchView::Settings,
+                ViewSize {
+                    width: 700.0,
+                    height: 360.0,
+                },
+            ),
+        ]),
+        description: "Expanded size in points per tab (app, archive, settings); tabs without one use expanded_width and expanded_height".to_string(),
+    }
+}
+
+fn default_peek_zone_width() -> ConfigValue<f64> {
+    ConfigValue {
+        value: 520.0,
+        description: "Width in points of the hover detection zone at the peek stage".to_string(),
+    }
+}
+
+fn default_peek_zone_height() -> ConfigValue<f64> {
+    ConfigValue {
+        value: 90.0,
+        description: "Height in points of the hover detection zone at the peek stage".to_string(),
+    }
+}
+
+fn default_idle_poll_interval_ms() -> ConfigValue<u64> {
+    ConfigValue {
+        value: 250,
+        description: "Mouse polling interval in milliseconds while the pointer is far from every hover zone".to_string(),
+    }
+}
+
+fn default_near_distance() -> ConfigValue<f64> {
+    ConfigValue {
+        value: 200.0,
+        description: "Distance in points from a hover zone within which polling runs at poll_interval_ms".to_string(),
+    }
+}
+
+fn default_velocity_threshold() -> ConfigValue<f64> {
+    ConfigValue {
+        value: 1200.0,
+        description: "Pointer speed in points per second above which entering the zone doesn't arm expansion (0 = off)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn animation(section: serde_json::Value) -> AnimationConfig {
+        serde_json::from_value(section).unwrap()
+    }
+
+    fn bezier(duration: f64, points: &[f64]) -> AnimationModel {
+        AnimationModel::Bezier {
+            duration,
+            points: points.to_vec(),
+        }
+    }
+
+    #[test]
+    fn legacy_keys_become_beziers() {
+        let config = animation(json!({
+            "expand_duration": { "value": 1.5, "description": "" },
+            "collapse_duration": { "value": 0.2, "description": "" },
+            "expand_timing": { "value": [0.16, 1.0, 0.3, 1.0], "description": "" },
+            "collapse_timing": { "value": [0.4, 0.0, 0.2, 1.0], "description": "" },
+            "spring": { "value": false, "description": "" }
+        }));
+        assert_eq!(config.expand.value, bezier(1.5, &[0.16, 1.0, 0.3, 1.0]));
+        assert_eq!(config.collapse.value, bezier(0.2, &[0.4, 0.0, 0.2, 1.0]));
+    }
+
+    #[test]
+    fn missing_legacy_keys_take_their_old_defaults() {
+        let config = animation(json!({
+            "collapse_timing": { "value": [0.4, 0.0, 0.2, 1.0], "description": "" }
+        }));
+        assert_eq!(
+            config.expand.value,
+            NotchConfig::default().animation.expand.value
+        );
+        assert_eq!(config.collapse.value, bezier(0.35, &[0.4, 0.0, 0.2, 1.0]));
+    }
+
+    #[test]
+    fn legacy_spring_switch_uses_the_default_spring() {
+        let config = animation(json!({
+            "expand_duration": { "value": 1.5, "description": "" },
+            "spring": { "value": true, "description": "" }
+        }));
+        let spring = Spring::default();
+        assert_eq!(config.expand.value.spring(), Some(spring));
+        assert_eq!(config.collapse.value.spring(), Some(spring));
+    }
+
+    #[test]
+    fn models_win_over_legacy_keys() {
+        let config = animation(json!({
+            "collapse": {
+                "value": { "model": "bezier", "duration": 0.25, "points": [0.0, 0.0, 1.0, 1.0] },
+                "description": "Collapse animation"
+            },
+            "collapse_duration": { "value": 2.0, "description": "" }
+        }));
+        assert_eq!(config.collapse.value, bezier(0.25, &[0.0, 0.0, 1.0, 1.0]));
+        assert_eq!(config.collapse.description, "Collapse animation");
+    }
+
+    #[test]
+    fn bad_audio_values_fall_back_to_the_defaults() {
+        let defaults = AudioConfig::default();
+        let mut audio = AudioConfig::default();
+        audio.min_frequency.value = 30_000.0;
+        audio.max_frequency.value = 40_000.0;
+        audio.bands.value = 0;
+        audio.fft_size.value = 1 << 20;
+        audio.max_fps.value = 0;
+        audio.smoothing.value = 0.9;
+        audio.validate();
+        assert_eq!(audio.min_frequency.value, defaults.min_frequency.value);
+        assert_eq!(audio.max_frequency.value, defaults.max_frequency.value);
+        assert_eq!(audio.bands.value, defaults.bands.value);
+        assert_eq!(audio.fft_size.value, defaults.fft_size.value);
+        assert_eq!(audio.max_fps.value, defaults.max_fps.value);
+        assert_eq!(audio.smoothing.value, 0.9);
+
+        // Each frequency is fine alone, but not as a range
+        audio.min_frequency.value = 5_000.0;
+        audio.max_frequency.value = 1_000.0;
+        audio.validate();
+        assert_eq!(audio.min_frequency.value, defaults.min_frequency.value);
+        assert_eq!(audio.max_frequency.value, defaults.max_frequency.value);
+
+        audio.min_frequency.value = 100.0;
+        audio.max_frequency.value = 20_000.0;
+        audio.validate();
+        assert_eq!(audio.min_frequency.value, 100.0);
+        assert_eq!(audio.max_frequency.value, 20_000.0);
+    }
+
+    #[test]
+    fn bad_animation_section_keeps_the_rest() {
+        let mut file = serde_json::to_value(NotchConfig::default()).unwrap();
+        file["animation"] =
+            json!({ "expand": { "value": { "model": "wobble" }, "description": "" } });
+        file["dimensions"]["corner_radius"]["value"] = json!(20.0);
+        let config: NotchConfig = serde_json::from_value(file).unwrap();
+        assert_eq!(
+            config.animation.expand.value,
+            NotchConfig::default().animation.expand.value
+        );
+        assert_eq!(config.dimensions.corner_radius.value, 20.0);
+    }
+}
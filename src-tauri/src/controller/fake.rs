@@ -0,0 +1,275 @@
+use super::{ControllerTracker, NotchController, NotchFrames, NotchPosition};
+use crate::state::NotchStage;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ControllerCall {
+    Attach(NotchFrames),
+    Show(NotchStage),
+    Progress(f64),
+    ResizeExpanded(f64, f64),
+}
+
+/// Records the operations a real controller would have carried out, i.e. the
+/// ones that changed something.
+#[derive(Default)]
+pub struct RecordingController {
+    tracker: Mutex<ControllerTracker>,
+    calls: Mutex<Vec<ControllerCall>>,
+}
+
+impl RecordingController {
+    pub fn calls(&self) -> Vec<ControllerCall> {
+        self.calls
+            .lock()
+            .map(|calls| calls.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn position(&self) -> Option<NotchPosition> {
+        self.tracker.lock().map(|tracker| tracker.position()).ok()
+    }
+
+    fn record(&self, call: ControllerCall) {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(call);
+        }
+    }
+
+    fn move_to(&self, position: NotchPosition, call: ControllerCall) -> Result<(), String> {
+        let moved = self
+            .tracker
+            .lock()
+            .map_err(|_| "controller state poisoned".to_string())?
+            .move_to(position)?
+            .is_some();
+        if moved {
+            self.record(call);
+        }
+        Ok(())
+    }
+}
+
+impl NotchController for RecordingController {
+    fn attach(&self, frames: NotchFrames) -> Result<(), String> {
+        let changed = self
+            .tracker
+            .lock()
+            .map_err(|_| "controller state poisoned".to_string())?
+            .attach(frames);
+        if changed {
+            self.record(ControllerCall::Attach(frames));
+        }
+        Ok(())
+    }
+
+    fn show(&self, stage: NotchStage) -> Result<(), String> {
+        self.move_to(NotchPosition::Stage(stage), ControllerCall::Show(stage))
+    }
+
+    fn set_progress(&self, progress: f64) -> Result<(), String> {
+        let progress = progress.clamp(0.0, 1.0);
+        self.move_to(
+            NotchPosition::Progress(progress),
+            ControllerCall::Progress(progress),
+        )
+    }
+
+    fn resize_expanded(&self, width: f64, height: f64) -> Result<(), String> {
+        let resized = self
+            .tracker
+            .lock()
+            .map_err(|_| "controller state poisoned".to_string())?
+            .resize_expanded(width, height)?
+            .is_some();
+        if resized {
+            self.record(ControllerCall::ResizeExpanded(width, height));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::transition;
+    use crate::state::{NotchPhase, NotchState, NotchStore};
+
+    fn frames() -> NotchFrames {
+        NotchFrames {
+            closed_width: 200.0,
+            closed_height: 32.0,
+            expanded_width: 700.0,
+            expanded_height: 200.0,
+            peek_width: 300.0,
+            peek_height: 60.0,
+            corner_radius: 12.0,
+        }
+    }
+
+    #[test]
+    fn repeats_are_recorded_once() {
+        let controller = RecordingController::default();
+        controller.attach(frames()).unwrap();
+        controller.attach(frames()).unwrap();
+        controller.show(NotchStage::Expanded).unwrap();
+        controller.show(NotchStage::Expanded).unwrap();
+        controller.resize_expanded(700.0, 360.0).unwrap();
+        controller.resize_expanded(700.0, 360.0).unwrap();
+        // Already compact
+        controller.show(NotchStage::Compact).unwrap();
+        controller.show(NotchStage::Compact).unwrap();
+
+        assert_eq!(
+            controller.calls(),
+            [
+                ControllerCall::Attach(frames()),
+                ControllerCall::Show(NotchStage::Expanded),
+                ControllerCall::ResizeExpanded(700.0, 360.0),
+                ControllerCall::Show(NotchStage::Compact),
+            ]
+        );
+    }
+
+    #[test]
+    fn starts_compact() {
+        let controller = RecordingController::default();
+        controller.attach(frames()).unwrap();
+        controller.show(NotchStage::Compact).unwrap();
+        assert_eq!(controller.calls(), [ControllerCall::Attach(frames())]);
+        assert_eq!(
+            controller.position(),
+            Some(NotchPosition::Stage(NotchStage::Compact))
+        );
+    }
+
+    #[test]
+    fn progress_is_clamped() {
+        let controller = RecordingController::default();
+        controller.attach(frames()).unwrap();
+        controller.set_progress(0.4).unwrap();
+        controller.set_progress(1.5).unwrap();
+        assert_eq!(
+            controller.position(),
+            Some(NotchPosition::Stage(NotchStage::Expanded))
+        );
+        // Past either end is the stage already there
+        controller.show(NotchStage::Expanded).unwrap();
+        controller.set_progress(2.0).unwrap();
+        controller.set_progress(-0.5).unwrap();
+        assert_eq!(
+            controller.position(),
+            Some(NotchPosition::Stage(NotchStage::Compact))
+        );
+
+        assert_eq!(
+            controller.calls(),
+            [
+                ControllerCall::Attach(frames()),
+                ControllerCall::Progress(0.4),
+                ControllerCall::Progress(1.0),
+                ControllerCall::Progress(0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn calls_before_attach_fail() {
+        let controller = RecordingController::default();
+        assert!(controller.show(NotchStage::Expanded).is_err());
+        assert!(controller.set_progress(0.5).is_err());
+        assert!(controller.resize_expanded(700.0, 360.0).is_err());
+        assert!(controller.calls().is_empty());
+        assert_eq!(
+            controller.position(),
+            Some(NotchPosition::Stage(NotchStage::Compact))
+        );
+    }
+
+    // Runs `transition`, returning its result and how many state changes it emitted
+    fn go(
+        controller: &RecordingController,
+        store: &NotchStore,
+        stage: NotchStage,
+    ) -> (Result<bool, String>, usize) {
+        let emitted = Mutex::new(0);
+        let result = transition(controller, store, stage, |_| *emitted.lock().unwrap() += 1);
+        (result, emitted.into_inner().unwrap())
+    }
+
+    fn at(store: &NotchStore) -> (NotchPhase, NotchStage) {
+        let state = store.get();
+        (state.phase, state.stage)
+    }
+
+    #[test]
+    fn transitions_show_each_stage_once() {
+        let (controller, store) = (RecordingController::default(), NotchStore::default());
+        controller.attach(frames()).unwrap();
+
+        assert_eq!(go(&controller, &store, NotchStage::Peek), (Ok(false), 1));
+        assert_eq!(at(&store), (NotchPhase::Collapsed, NotchStage::Peek));
+        assert_eq!(
+            go(&controller, &store, NotchStage::Expanded),
+            (Ok(false), 1)
+        );
+        assert_eq!(at(&store), (NotchPhase::Expanding, NotchStage::Expanded));
+        // Asked again mid-animation: nothing changes, nothing is shown
+        assert_eq!(
+            go(&controller, &store, NotchStage::Expanded),
+            (Ok(false), 0)
+        );
+        assert_eq!(go(&controller, &store, NotchStage::Compact), (Ok(false), 1));
+        assert_eq!(at(&store), (NotchPhase::Collapsing, NotchStage::Compact));
+
+        assert_eq!(
+            controller.calls(),
+            [
+                ControllerCall::Attach(frames()),
+                ControllerCall::Show(NotchStage::Peek),
+                ControllerCall::Show(NotchStage::Expanded),
+                ControllerCall::Show(NotchStage::Compact),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_transition_restores_the_store() {
+        let (controller, store) = (RecordingController::default(), NotchStore::default());
+        store.apply(|state| state.pinned = true, |_| {});
+
+        // Not attached, so showing fails
+        let (result, emitted) = go(&controller, &store, NotchStage::Compact);
+        assert!(result.is_err());
+        // Unpinned by heading to compact from collapsed, then put back
+        assert_eq!(emitted, 2);
+        assert_eq!(
+            store.get(),
+            NotchState {
+                pinned: true,
+                ..NotchState::default()
+            }
+        );
+        assert!(controller.calls().is_empty());
+    }
+
+    #[test]
+    fn compact_from_collapsed_unpins() {
+        let (controller, store) = (RecordingController::default(), NotchStore::default());
+        controller.attach(frames()).unwrap();
+        store.apply(|state| state.pinned = true, |_| {});
+        assert_eq!(go(&controller, &store, NotchStage::Compact), (Ok(true), 1));
+        assert!(!store.get().pinned);
+
+        // Collapsing from expanded only unpins once it settles
+        store.apply(
+            |state| {
+                state.set_phase(NotchPhase::Expanded);
+                state.pinned = true;
+            },
+            |_| {},
+        );
+        assert_eq!(go(&controller, &store, NotchStage::Compact), (Ok(false), 1));
+        assert!(store.get().pinned);
+    }
+}
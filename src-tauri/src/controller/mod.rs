@@ -0,0 +1,242 @@
+#[cfg(target_os = "linux")]
+mod refresh;
+#[cfg(not(target_os = "macos"))]
+mod window;
+// Stands in for a real controller in tests, where there's no window
+#[cfg(test)]
+pub mod fake;
+
+#[cfg(not(target_os = "macos"))]
+pub use window::WindowController;
+
+use crate::state::{NotchStage, NotchState, NotchStore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+/// Emitted with `{ "phase": "expand" | "collapse", "stage" }` once the capsule
+/// has finished moving to a stage. `phase` is `expand` for the expanded stage
+/// and `collapse` for the others.
+pub const ANIM_END_EVENT: &str = "notch-native-anim-end";
+
+pub fn notify_anim_end(app: &AppHandle, stage: NotchStage) {
+    let phase = if stage == NotchStage::Expanded {
+        "expand"
+    } else {
+        "collapse"
+    };
+    let _ = app.emit(
+        ANIM_END_EVENT,
+        serde_json::json!({ "phase": phase, "stage": stage }),
+    );
+}
+
+/// Heads the store toward `stage` and has `controller` animate there, putting
+/// the store back if the controller fails. Changes go through
+/// [`NotchStore::apply`] with `emit`. Returns whether it unpinned, which
+/// heading to compact from a collapsed phase does (see [`NotchState::set_phase`]).
+pub fn transition(
+    controller: &dyn NotchController,
+    store: &NotchStore,
+    stage: NotchStage,
+    emit: impl Fn(&NotchState),
+) -> Result<bool, String> {
+    let before = store.get();
+    store.apply(
+        |state| {
+            let phase = state.phase.toward(stage == NotchStage::Expanded);
+            state.set_phase(phase);
+            state.stage = stage;
+        },
+        &emit,
+    );
+    if let Err(e) = controller.show(stage) {
+        store.apply(
+            |state| {
+                state.phase = before.phase;
+                state.stage = before.stage;
+                state.pinned = before.pinned;
+            },
+            &emit,
+        );
+        return Err(e);
+    }
+    Ok(before.pinned && !store.get().pinned)
+}
+
+/// Sizes the capsule moves between, in logical points.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotchFrames {
+    pub closed_width: f64,
+    pub closed_height: f64,
+    pub expanded_width: f64,
+    pub expanded_height: f64,
+    pub peek_width: f64,
+    pub peek_height: f64,
+    pub corner_radius: f64,
+}
+
+impl NotchFrames {
+    /// Width and height `progress` of the way from closed (0) to expanded (1).
+    pub fn size_at(&self, progress: f64) -> (f64, f64) {
+        let progress = progress.clamp(0.0, 1.0);
+        (
+            self.closed_width + (self.expanded_width - self.closed_width) * progress,
+            self.closed_height + (self.expanded_height - self.closed_height) * progress,
+        )
+    }
+
+    pub fn size(&self, position: NotchPosition) -> (f64, f64) {
+        match position {
+            NotchPosition::Stage(NotchStage::Compact) => (self.closed_width, self.closed_height),
+            NotchPosition::Stage(NotchStage::Peek) => (self.peek_width, self.peek_height),
+            NotchPosition::Stage(NotchStage::Expanded) => {
+                (self.expanded_width, self.expanded_height)
+            }
+            NotchPosition::Progress(progress) => self.size_at(progress),
+        }
+    }
+
+    /// Whether moving between positions makes the capsule bigger, which picks
+    /// the expand animation over the collapse one.
+    pub fn grows(&self, from: NotchPosition, to: NotchPosition) -> bool {
+        larger(self.size(to), self.size(from))
+    }
+
+    /// Whether `to`'s expanded size is bigger than this one's.
+    pub fn expanded_grows(&self, to: &NotchFrames) -> bool {
+        let at = NotchPosition::Stage(NotchStage::Expanded);
+        larger(to.size(at), self.size(at))
+    }
+}
+
+// Height decides, then width
+fn larger(a: (f64, f64), b: (f64, f64)) -> bool {
+    a.1 > b.1 || (a.1 == b.1 && a.0 > b.0)
+}
+
+/// Where the capsule is: at a stage, or part way from closed to expanded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NotchPosition {
+    Stage(NotchStage),
+    Progress(f64),
+}
+
+impl NotchPosition {
+    /// Clamps progress, and treats its ends as the stages they match.
+    fn normalized(self) -> Self {
+        let NotchPosition::Progress(progress) = self else {
+            return self;
+        };
+        let progress = progress.clamp(0.0, 1.0);
+        if progress == 0.0 {
+            NotchPosition::Stage(NotchStage::Compact)
+        } else if progress == 1.0 {
+            NotchPosition::Stage(NotchStage::Expanded)
+        } else {
+            NotchPosition::Progress(progress)
+        }
+    }
+}
+
+/// Moves the capsule between its stages. Every operation is idempotent: repeating
+/// one, or asking for where the capsule already is, does nothing, so callers
+/// don't have to track what they already asked for.
+pub trait NotchController: Send + Sync {
+    /// Sets the frames. Must come first; attaching again with different frames
+    /// keeps the current position.
+    fn attach(&self, frames: NotchFrames) -> Result<(), String>;
+
+    /// Animates to `stage` from wherever the capsule is, even mid-animation,
+    /// and emits [`ANIM_END_EVENT`] when done.
+    fn show(&self, stage: NotchStage) -> Result<(), String>;
+
+    /// Jumps to `progress` between closed (0) and expanded (1), e.g. to follow
+    /// a gesture. Clamped to that range.
+    fn set_progress(&self, progress: f64) -> Result<(), String>;
+
+    /// Changes the expanded size, e.g. for a tab that needs more room. An
+    /// expanded capsule animates to it and emits [`ANIM_END_EVENT`]; otherwise
+    /// it applies from the next expand.
+    fn resize_expanded(&self, width: f64, height: f64) -> Result<(), String>;
+
+    /// Whether the controller sizes the capsule window itself. Otherwise the
+    /// window stays at its expanded size and the frontend resizes it.
+    fn resizes_window(&self) -> bool {
+        false
+    }
+}
+
+pub type SharedController = Arc<dyn NotchController>;
+
+/// What the capsule was last asked to be, so controllers only act on changes.
+#[derive(Debug)]
+pub struct ControllerTracker {
+    frames: Option<NotchFrames>,
+    position: NotchPosition,
+}
+
+impl Default for ControllerTracker {
+    fn default() -> Self {
+        Self {
+            frames: None,
+            position: NotchPosition::Stage(NotchStage::Compact),
+        }
+    }
+}
+
+impl ControllerTracker {
+    /// Records `frames`; false if they're the ones already attached.
+    pub fn attach(&mut self, frames: NotchFrames) -> bool {
+        if self.frames == Some(frames) {
+            return false;
+        }
+        self.frames = Some(frames);
+        true
+    }
+
+    /// Forgets the frames, e.g. after attaching failed, so the next attach runs.
+    pub fn detach(&mut self) {
+        self.frames = None;
+    }
+
+    pub fn position(&self) -> NotchPosition {
+        self.position
+    }
+
+    /// Records a new expanded size, returning the frames before and after, or
+    /// `None` if it's unchanged. Fails before the first [`attach`](Self::attach).
+    pub fn resize_expanded(
+        &mut self,
+        width: f64,
+        height: f64,
+    ) -> Result<Option<(NotchFrames, NotchFrames)>, String> {
+        let before = self.frames.ok_or("notch controller is not attached")?;
+        let after = NotchFrames {
+            expanded_width: width,
+            expanded_height: height,
+            ..before
+        };
+        if after == before {
+            return Ok(None);
+        }
+        self.frames = Some(after);
+        Ok(Some((before, after)))
+    }
+
+    /// Records a move to `position`, returning the frames to move within and
+    /// where the capsule was, or `None` if it's already there. Fails before the
+    /// first [`attach`](Self::attach).
+    pub fn move_to(
+        &mut self,
+        position: NotchPosition,
+    ) -> Result<Option<(NotchFrames, NotchPosition)>, String> {
+        let frames = self.frames.ok_or("notch controller is not attached")?;
+        let position = position.normalized();
+        if position == self.position {
+            return Ok(None);
+        }
+        let from = std::mem::replace(&mut self.position, position);
+        Ok(Some((frames, from)))
+    }
+}
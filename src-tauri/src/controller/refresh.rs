@@ -0,0 +1,15 @@
+use gtk::prelude::*;
+use tauri::WebviewWindow;
+
+/// Refresh rate in Hz of the monitor showing `window`, if GDK knows it. Main
+/// thread only.
+pub fn refresh_rate(window: &WebviewWindow) -> Option<f64> {
+    let gtk_window = window.gtk_window().ok()?;
+    let gdk_window = WidgetExt::window(&gtk_window)?;
+    let monitor = WidgetExt::display(&gtk_window).monitor_at_window(&gdk_window)?;
+    // GDK reports millihertz, and 0 when it doesn't know
+    match monitor.refresh_rate() {
+        rate if rate > 0 => Some(rate as f64 / 1000.0),
+        _ => None,
+    }
+}
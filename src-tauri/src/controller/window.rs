@@ -0,0 +1,277 @@
+use super::{notify_anim_end, ControllerTracker, NotchController, NotchFrames, NotchPosition};
+use crate::animation::{Easing, Timeline};
+use crate::config::NotchConfig;
+use crate::state::NotchStage;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, LogicalSize, PhysicalPosition, WebviewWindow};
+
+// When the display's refresh rate can't be read
+const DEFAULT_FRAME: Duration = Duration::from_micros(16_667);
+
+/// Resizes the capsule window itself, keeping it centred at the top of its
+/// monitor. Width and height animate on their own [`Timeline`]s at the
+/// display's refresh rate, so moving to another stage mid-flight carries on
+/// from the current size. For platforms without a native mask animator.
+pub struct WindowController {
+    app: AppHandle,
+    window: WebviewWindow,
+    tracker: Mutex<ControllerTracker>,
+    motion: Arc<Mutex<Motion>>,
+}
+
+// What the animation thread works from; retargeted in place while it runs
+struct Motion {
+    width: Timeline,
+    height: Timeline,
+    // Reported when the animation ends, unless it was heading to a progress
+    stage: Option<NotchStage>,
+    // Bumped by every retarget and jump, so the thread can tell whether the
+    // segment it saw finish is still the current one
+    generation: u64,
+    frame: Duration,
+    running: bool,
+}
+
+impl Motion {
+    fn size(&self, now: Instant) -> (f64, f64) {
+        (self.width.value(now), self.height.value(now))
+    }
+
+    // Bezier durations are for the full distance between closed and expanded
+    fn set_spans(&mut self, frames: &NotchFrames) {
+        self.width
+            .set_span(frames.expanded_width - frames.closed_width);
+        self.height
+            .set_span(frames.expanded_height - frames.closed_height);
+    }
+
+    fn jump_to(&mut self, (width, height): (f64, f64)) {
+        self.width.jump_to(width);
+        self.height.jump_to(height);
+        self.generation += 1;
+    }
+}
+
+impl WindowController {
+    pub fn new(app: &AppHandle, window: WebviewWindow) -> Self {
+        Self {
+            app: app.clone(),
+            window,
+            tracker: Mutex::new(ControllerTracker::default()),
+            motion: Arc::new(Mutex::new(Motion {
+                width: Timeline::at(0.0),
+                height: Timeline::at(0.0),
+                stage: None,
+                generation: 0,
+                frame: DEFAULT_FRAME,
+                running: false,
+            })),
+        }
+    }
+
+    fn lock_motion(&self) -> Result<std::sync::MutexGuard<'_, Motion>, String> {
+        self.motion
+            .lock()
+            .map_err(|_| "controller state poisoned".to_string())
+    }
+
+    // The tracker's frames and where the capsule was, if it has to move at all
+    fn move_to(
+        &self,
+        position: NotchPosition,
+    ) -> Result<Option<(NotchFrames, NotchPosition)>, String> {
+        self.tracker
+            .lock()
+            .map_err(|_| "controller state poisoned".to_string())?
+            .move_to(position)
+    }
+
+    /// Animates to `stage` with the expand or collapse curve, as `growing`
+    /// picks, starting the animation thread unless it's already running, in
+    /// which case it just follows the new target.
+    fn animate(&self, frames: NotchFrames, growing: bool, stage: NotchStage) -> Result<(), String> {
+        let easing = Easing::from_config(&NotchConfig::get().animation, growing);
+        let (width, height) = frames.size(NotchPosition::Stage(stage));
+        {
+            let mut motion = self.lock_motion()?;
+            let now = Instant::now();
+            motion.width.animate_to(now, width, easing);
+            motion.height.animate_to(now, height, easing);
+            motion.stage = Some(stage);
+            motion.generation += 1;
+            if motion.running {
+                return Ok(());
+            }
+            motion.running = true;
+        }
+
+        let app = self.app.clone();
+        let window = self.window.clone();
+        let motion = self.motion.clone();
+        thread::spawn(move || loop {
+            let Ok(mut state) = motion.lock() else {
+                return;
+            };
+            let now = Instant::now();
+            let size = state.size(now);
+            let done = !state.width.is_animating(now) && !state.height.is_animating(now);
+            let (stage, generation, frame) = (state.stage, state.generation, state.frame);
+            if done {
+                state.running = false;
+            }
+            drop(state);
+
+            if let Err(e) = place(&window, size) {
+                eprintln!("Notch window animation: {}", e);
+            }
+            if done {
+                let Ok(mut state) = motion.lock() else {
+                    return;
+                };
+                // Emitted under the lock, so a set_progress can't cancel the
+                // segment between the check and the event
+                if state.generation == generation {
+                    if let Some(stage) = stage {
+                        notify_anim_end(&app, stage);
+                    }
+                    return;
+                }
+                // A new animation took over, with a thread of its own
+                if state.running {
+                    return;
+                }
+                // Jumped while this frame was placed; place the jump again
+                state.running = true;
+            }
+            thread::sleep(frame);
+        });
+        Ok(())
+    }
+
+    // Display refresh rate as a frame interval, read on the main thread
+    fn frame_interval(&self) -> Duration {
+        let window = self.window.clone();
+        let (tx, rx) = channel();
+        let scheduled = self.app.run_on_main_thread(move || {
+            let _ = tx.send(refresh_rate(&window));
+        });
+        match (scheduled, rx.recv()) {
+            (Ok(()), Ok(Some(hz))) if hz >= 1.0 => Duration::from_secs_f64(1.0 / hz),
+            _ => DEFAULT_FRAME,
+        }
+    }
+}
+
+impl NotchController for WindowController {
+    fn attach(&self, frames: NotchFrames) -> Result<(), String> {
+        let position = {
+            let mut tracker = self
+                .tracker
+                .lock()
+                .map_err(|_| "controller state poisoned".to_string())?;
+            if !tracker.attach(frames) {
+                return Ok(());
+            }
+            tracker.position()
+        };
+        let frame = self.frame_interval();
+        let size = frames.size(position);
+        {
+            let mut motion = self.lock_motion()?;
+            motion.set_spans(&frames);
+            motion.jump_to(size);
+            motion.frame = frame;
+        }
+        let result = place(&self.window, size);
+        if result.is_err() {
+            // Let the next attach try again
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.detach();
+            }
+        }
+        result
+    }
+
+    fn show(&self, stage: NotchStage) -> Result<(), String> {
+        let to = NotchPosition::Stage(stage);
+        match self.move_to(to)? {
+            Some((frames, from)) => self.animate(frames, frames.grows(from, to), stage),
+            None => Ok(()),
+        }
+    }
+
+    fn set_progress(&self, progress: f64) -> Result<(), String> {
+        let Some((frames, _)) = self.move_to(NotchPosition::Progress(progress))? else {
+            return Ok(());
+        };
+        let size = frames.size_at(progress);
+        {
+            // A running animation thread stops at this size on its next frame
+            let mut motion = self.lock_motion()?;
+            motion.jump_to(size);
+            motion.stage = None;
+        }
+        place(&self.window, size)
+    }
+
+    fn resize_expanded(&self, width: f64, height: f64) -> Result<(), String> {
+        let (resized, position) = {
+            let mut tracker = self
+                .tracker
+                .lock()
+                .map_err(|_| "controller state poisoned".to_string())?;
+            (tracker.resize_expanded(width, height)?, tracker.position())
+        };
+        let Some((before, after)) = resized else {
+            return Ok(());
+        };
+        self.lock_motion()?.set_spans(&after);
+        match position {
+            NotchPosition::Stage(NotchStage::Expanded) => {
+                self.animate(after, before.expanded_grows(&after), NotchStage::Expanded)
+            }
+            NotchPosition::Progress(progress) => {
+                let size = after.size_at(progress);
+                self.lock_motion()?.jump_to(size);
+                place(&self.window, size)
+            }
+            // Applies from the next expand
+            NotchPosition::Stage(_) => Ok(()),
+        }
+    }
+
+    fn resizes_window(&self) -> bool {
+        true
+    }
+}
+
+/// Sizes the window, in logical points, centred at the top of its monitor.
+fn place(window: &WebviewWindow, (width, height): (f64, f64)) -> Result<(), String> {
+    window
+        .set_size(LogicalSize::new(width, height))
+        .map_err(|e| e.to_string())?;
+    if let Ok(Some(monitor)) = window.current_monitor() {
+        let scale = monitor.scale_factor();
+        let x = monitor.position().x as f64 + (monitor.size().width as f64 - width * scale) / 2.0;
+        window
+            .set_position(PhysicalPosition::new(
+                x.round() as i32,
+                monitor.position().y,
+            ))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn refresh_rate(window: &WebviewWindow) -> Option<f64> {
+    super::refresh::refresh_rate(window)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn refresh_rate(_window: &WebviewWindow) -> Option<f64> {
+    None
+}
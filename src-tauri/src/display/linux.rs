@@ -0,0 +1,80 @@
+use super::DisplayMetrics;
+use gtk::prelude::*;
+use tauri::Monitor;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window};
+
+/// The monitor hosting `window`, with the height of the top panel above its
+/// work area. Main thread only.
+pub fn display_metrics(window: &tauri::Window) -> Option<DisplayMetrics> {
+    let monitor = window.current_monitor().ok().flatten()?;
+    let scale = monitor.scale_factor();
+    let panel = x11_workarea()
+        .and_then(|area| top_panel_px(&monitor, area))
+        .map(|px| px / scale)
+        .or_else(|| gdk_top_panel(window));
+    Some(DisplayMetrics {
+        scale,
+        menu_bar_pts: panel.filter(|pts| *pts > 0.0),
+        notch: None,
+    })
+}
+
+/// The current desktop's `_NET_WORKAREA` as `[x, y, width, height]` in root
+/// window pixels. Set by most X11 window managers, and by some compositors on
+/// XWayland.
+fn x11_workarea() -> Option<[i64; 4]> {
+    let (conn, screen) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen)?.root;
+    let atom = |name: &str| {
+        let reply = conn.intern_atom(true, name.as_bytes()).ok()?.reply().ok()?;
+        (reply.atom != 0).then_some(reply.atom)
+    };
+    let desktop = atom("_NET_CURRENT_DESKTOP")
+        .and_then(|current| cardinals(&conn, root, current))
+        .and_then(|values| values.first().copied())
+        .unwrap_or(0);
+    let areas = cardinals(&conn, root, atom("_NET_WORKAREA")?)?;
+    // One area per desktop; fall back to the first if the desktop has none
+    let area = areas
+        .chunks_exact(4)
+        .nth(desktop as usize)
+        .or_else(|| areas.chunks_exact(4).next())?;
+    Some([
+        area[0] as i64,
+        area[1] as i64,
+        area[2] as i64,
+        area[3] as i64,
+    ])
+}
+
+fn cardinals(conn: &impl Connection, root: Window, property: Atom) -> Option<Vec<u32>> {
+    let reply = conn
+        .get_property(false, root, property, AtomEnum::CARDINAL, 0, 1024)
+        .ok()?
+        .reply()
+        .ok()?;
+    reply.value32().map(|values| values.collect())
+}
+
+// The gap between the monitor's top edge and the work area's, in physical
+// pixels. `_NET_WORKAREA` is one rectangle across all monitors, so it only
+// counts where the work area's top edge falls on this monitor.
+fn top_panel_px(monitor: &Monitor, [x, y, width, _]: [i64; 4]) -> Option<f64> {
+    let position = monitor.position();
+    let size = monitor.size();
+    let (left, top) = (position.x as i64, position.y as i64);
+    let (right, bottom) = (left + size.width as i64, top + size.height as i64);
+    let overlaps = x < right && x + width > left;
+    (overlaps && y >= top && y < bottom).then(|| (y - top) as f64)
+}
+
+// GDK's per-monitor work area, in points; the fallback without
+// `_NET_WORKAREA`. Wayland compositors rarely report one, in which case it
+// matches the monitor and there's no panel.
+fn gdk_top_panel(window: &tauri::Window) -> Option<f64> {
+    let gtk_window = window.gtk_window().ok()?;
+    let gdk_window = WidgetExt::window(&gtk_window)?;
+    let monitor = WidgetExt::display(&gtk_window).monitor_at_window(&gdk_window)?;
+    Some((monitor.workarea().y() - monitor.geometry().y()) as f64)
+}
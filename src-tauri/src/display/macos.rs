@@ -0,0 +1,46 @@
+use super::{DisplayMetrics, HardwareNotch};
+use core::ffi::c_void;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::{NSScreen, NSWindow};
+use objc2_foundation::MainThreadMarker;
+
+/// The screen hosting `window` (or the main screen), with its notch if it has
+/// one (macOS 12+). Must run on the main thread.
+pub fn display_metrics(window: &tauri::Window) -> Option<DisplayMetrics> {
+    // NSWindow pointer
+    let raw: *mut c_void = window.ns_window().ok()?;
+    if raw.is_null() {
+        return None;
+    }
+    let obj: *mut AnyObject = raw.cast();
+    let ns_win: &NSWindow = unsafe { &*(obj as *mut NSWindow) };
+
+    // Screen
+    let screen = ns_win.screen().or_else(|| {
+        let mtm = MainThreadMarker::new()?;
+        NSScreen::mainScreen(mtm)
+    })?;
+
+    // Menu bar: the strip above the visible frame; external displays have one too
+    let frame = screen.frame();
+    let visible = screen.visibleFrame();
+    let menu_bar = (frame.origin.y + frame.size.height) - (visible.origin.y + visible.size.height);
+
+    // Notch presence
+    let insets = screen.safeAreaInsets();
+    let notch = (insets.top > 0.0).then(|| {
+        // True notch width
+        let left = screen.auxiliaryTopLeftArea();
+        let right = screen.auxiliaryTopRightArea();
+        HardwareNotch {
+            width_pts: (frame.size.width - left.size.width - right.size.width).max(0.0),
+            top_inset_pts: insets.top,
+        }
+    });
+
+    Some(DisplayMetrics {
+        scale: screen.backingScaleFactor() as f64,
+        menu_bar_pts: (menu_bar > 0.0).then_some(menu_bar),
+        notch,
+    })
+}
@@ -0,0 +1,110 @@
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+
+use crate::config::{NotchConfig, SyntheticNotchConfig};
+
+/// Synthetic notch height when neither the config nor the display gives one.
+const DEFAULT_INSET: f64 = 32.0;
+
+/// The notch the capsule hangs from, in points and physical pixels. `synthetic`
+/// is set when the display has no hardware notch and these come from
+/// `synthetic_notch`.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub struct NotchDimensions {
+    pub width_pts: f64,
+    pub top_inset_pts: f64,
+    pub width_px: f64,
+    pub top_inset_px: f64,
+    pub scale: f64,
+    pub synthetic: bool,
+}
+
+/// A hardware notch, in points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HardwareNotch {
+    pub width_pts: f64,
+    pub top_inset_pts: f64,
+}
+
+/// What the platform knows about the display hosting the capsule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayMetrics {
+    pub scale: f64,
+    /// Height of the menu bar or top panel, if there is one.
+    pub menu_bar_pts: Option<f64>,
+    pub notch: Option<HardwareNotch>,
+}
+
+/// Picks the notch for a display: the hardware one where there is one,
+/// otherwise a synthetic one sized from `synthetic_notch` and the menu bar.
+pub struct NotchGeometry<'a> {
+    config: &'a SyntheticNotchConfig,
+}
+
+impl<'a> NotchGeometry<'a> {
+    pub fn new(config: &'a SyntheticNotchConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn resolve(&self, display: &DisplayMetrics) -> NotchDimensions {
+        match display.notch {
+            Some(notch) if notch.width_pts > 0.0 && notch.top_inset_pts > 0.0 => {
+                dimensions(notch.width_pts, notch.top_inset_pts, display.scale, false)
+            }
+            _ => {
+                let inset = positive(self.config.inset.value)
+                    .or(display.menu_bar_pts.and_then(positive))
+                    .unwrap_or(DEFAULT_INSET);
+                let scale = positive(self.config.scale.value).unwrap_or(display.scale);
+                dimensions(self.config.width.value.max(0.0), inset, scale, true)
+            }
+        }
+    }
+}
+
+fn positive(value: f64) -> Option<f64> {
+    (value > 0.0).then_some(value)
+}
+
+fn dimensions(width_pts: f64, top_inset_pts: f64, scale: f64, synthetic: bool) -> NotchDimensions {
+    let scale = positive(scale).unwrap_or(1.0);
+    NotchDimensions {
+        width_pts,
+        top_inset_pts,
+        width_px: width_pts * scale,
+        top_inset_px: top_inset_pts * scale,
+        scale,
+        synthetic,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn display_metrics(window: &tauri::Window) -> Option<DisplayMetrics> {
+    macos::display_metrics(window)
+}
+
+#[cfg(target_os = "linux")]
+fn display_metrics(window: &tauri::Window) -> Option<DisplayMetrics> {
+    linux::display_metrics(window)
+}
+
+// Only the monitor's scale is known here
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn display_metrics(window: &tauri::Window) -> Option<DisplayMetrics> {
+    let monitor = window.current_monitor().ok().flatten()?;
+    Some(DisplayMetrics {
+        scale: monitor.scale_factor(),
+        menu_bar_pts: None,
+        notch: None,
+    })
+}
+
+/// The notch on the display hosting the capsule; synthetic where there's no
+/// hardware notch (external monitors, Linux). `None` only without a display.
+#[tauri::command]
+pub fn get_notch_dimensions(window: tauri::Window) -> Option<NotchDimensions> {
+    let display = display_metrics(&window)?;
+    Some(NotchGeometry::new(&NotchConfig::get().synthetic_notch).resolve(&display))
+}
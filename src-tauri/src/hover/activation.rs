@@ -0,0 +1,93 @@
+use crate::config::ActivationConfig;
+use serde::Deserialize;
+
+/// Modifier keys held while a pointer sample was taken.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    /// Option on macOS, Alt elsewhere.
+    pub alt: bool,
+    /// Command on macOS, Super elsewhere.
+    pub meta: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    Shift,
+    Control,
+    Alt,
+    Meta,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "shift" => Some(Modifier::Shift),
+            "control" | "ctrl" => Some(Modifier::Control),
+            "option" | "alt" => Some(Modifier::Alt),
+            "command" | "cmd" | "super" | "meta" => Some(Modifier::Meta),
+            _ => None,
+        }
+    }
+}
+
+impl Modifiers {
+    pub fn has(&self, modifier: Modifier) -> bool {
+        match modifier {
+            Modifier::Shift => self.shift,
+            Modifier::Control => self.control,
+            Modifier::Alt => self.alt,
+            Modifier::Meta => self.meta,
+        }
+    }
+}
+
+/// What expands the capsule, from `activation.mode`. Collapsing works the same
+/// in every mode: the pointer leaves the zone for `collapse_delay_ms`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivationMode {
+    Hover,
+    Click,
+    /// Hover, but only entries made while the modifier is held count.
+    HoverWithModifier(Modifier),
+    /// Hover or click, whichever comes first.
+    Hybrid,
+}
+
+impl ActivationMode {
+    /// Unknown modes fall back to hover, unknown modifiers to option/alt.
+    pub fn from_config(config: &ActivationConfig) -> Self {
+        match config.mode.value.as_str() {
+            "click" => ActivationMode::Click,
+            "hover_with_modifier" => ActivationMode::HoverWithModifier(
+                Modifier::parse(&config.modifier.value).unwrap_or(Modifier::Alt),
+            ),
+            "hybrid" => ActivationMode::Hybrid,
+            _ => ActivationMode::Hover,
+        }
+    }
+
+    /// Whether dwelling in the zone may arm the expand delay.
+    pub fn hover_expands(&self, modifiers: Modifiers) -> bool {
+        match self {
+            ActivationMode::Hover | ActivationMode::Hybrid => true,
+            ActivationMode::Click => false,
+            ActivationMode::HoverWithModifier(modifier) => modifiers.has(*modifier),
+        }
+    }
+
+    pub fn click_expands(&self) -> bool {
+        matches!(self, ActivationMode::Click | ActivationMode::Hybrid)
+    }
+
+    /// Whether a sample inside the zone counts as being in it. Letting go of the
+    /// modifier in `HoverWithModifier` mode is treated like leaving the zone.
+    pub fn counts_inside(&self, modifiers: Modifiers) -> bool {
+        match self {
+            ActivationMode::HoverWithModifier(modifier) => modifiers.has(*modifier),
+            _ => true,
+        }
+    }
+}
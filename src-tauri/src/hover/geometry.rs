@@ -0,0 +1,777 @@
+use crate::config::NotchConfig;
+use crate::silhouette::Silhouette;
+use crate::state::{NotchStage, NotchView};
+
+/// An axis-aligned rectangle in global desktop coordinates. `y` is the edge with
+/// the smaller coordinate, which is the bottom on y-up systems and the top on
+/// y-down ones.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn max_x(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn max_y(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn center_x(&self) -> f64 {
+        self.x + self.width * 0.5
+    }
+
+    /// Edges are inclusive, so a pointer pinned against the top of the screen counts.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x <= self.max_x() && y >= self.y && y <= self.max_y()
+    }
+
+    /// Straight-line distance from `(x, y)` to the rect; 0 on or inside it.
+    pub fn distance_to(&self, x: f64, y: f64) -> f64 {
+        let dx = (self.x - x).max(x - self.max_x()).max(0.0);
+        let dy = (self.y - y).max(y - self.max_y()).max(0.0);
+        dx.hypot(dy)
+    }
+}
+
+/// Which way global y grows. macOS (Cocoa) is y-up from the bottom of the primary
+/// screen; X11, Wayland and Windows are y-down from the top-left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YAxis {
+    Up,
+    Down,
+}
+
+/// Unit of the frames and pointer positions. Zone sizes in the config are in
+/// points and get multiplied by each screen's scale factor for pixel layouts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Units {
+    Points,
+    Pixels,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenFrame {
+    pub frame: Rect,
+    pub scale_factor: f64,
+}
+
+/// All screens in one global coordinate space, in the same units the pointer is
+/// reported in. Origins can be negative (screens left of or above the primary).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScreenLayout {
+    pub screens: Vec<ScreenFrame>,
+    pub y_axis: YAxis,
+    pub units: Units,
+}
+
+/// Outline of a hover zone inside its bounding rect. Only the bottom edge (the
+/// one away from the screen edge) is shaped; the top always spans the full width.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoneShape {
+    Rectangle,
+    /// Bottom corners rounded with this radius.
+    Rounded {
+        radius: f64,
+    },
+    /// Sides narrow linearly by `taper` each toward the bottom, following the notch.
+    Trapezoid {
+        taper: f64,
+    },
+    /// The capsule's own outline (see [`Silhouette`]), with bottom corners of
+    /// this radius and shoulders for how far it's expanded.
+    Silhouette {
+        radius: f64,
+        progress: f64,
+    },
+}
+
+impl ZoneShape {
+    /// Reads `hover.zone`, with lengths already converted to layout units.
+    /// `size` is the zone's size in points, which the silhouette's corners
+    /// follow from the capsule's own.
+    fn from_config(
+        config: &NotchConfig,
+        (stage, view): (NotchStage, NotchView),
+        (width, height): (f64, f64),
+        scale: f64,
+    ) -> Self {
+        let zone = &config.hover.zone;
+        match zone.shape.value.as_str() {
+            "rounded" => ZoneShape::Rounded {
+                radius: zone.corner_radius.value.max(0.0) * scale,
+            },
+            "trapezoid" => ZoneShape::Trapezoid {
+                taper: zone.taper.value.max(0.0) * scale,
+            },
+            "silhouette" => ZoneShape::Silhouette {
+                radius: silhouette_radius(config, (stage, view), (width, height)) * scale,
+                progress: match stage {
+                    NotchStage::Compact => 0.0,
+                    NotchStage::Peek => 0.5,
+                    NotchStage::Expanded => 1.0,
+                },
+            },
+            _ => ZoneShape::Rectangle,
+        }
+    }
+}
+
+/// `dimensions.corner_radius` in points, grown or shrunk by as much as the zone
+/// of `size` is larger or smaller than the capsule it surrounds.
+fn silhouette_radius(
+    config: &NotchConfig,
+    (stage, view): (NotchStage, NotchView),
+    (width, height): (f64, f64),
+) -> f64 {
+    let dimensions = &config.dimensions;
+    let (capsule_width, capsule_height) = match stage {
+        NotchStage::Compact => (
+            dimensions.collapsed_width.value,
+            dimensions.collapsed_height.value,
+        ),
+        NotchStage::Peek => (dimensions.peek_width.value, dimensions.peek_height.value),
+        NotchStage::Expanded => dimensions.expanded_size(view),
+    };
+    let fit = if capsule_width > 0.0 && capsule_height > 0.0 {
+        (width / capsule_width).min(height / capsule_height)
+    } else {
+        1.0
+    };
+    dimensions.corner_radius.value.max(0.0) * fit
+}
+
+/// The hover zone on one screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HoverZone {
+    /// Index into [`ScreenLayout::screens`].
+    pub screen: usize,
+    /// Bounding rect in global coordinates.
+    pub rect: Rect,
+    pub shape: ZoneShape,
+    pub y_axis: YAxis,
+}
+
+impl HoverZone {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        if !self.rect.contains(x, y) {
+            return false;
+        }
+        let Rect { width, height, .. } = self.rect;
+        // Distances from the zone's left edge and from the screen edge.
+        let across = x - self.rect.x;
+        let depth = match self.y_axis {
+            YAxis::Up => self.rect.max_y() - y,
+            YAxis::Down => y - self.rect.y,
+        };
+
+        match self.shape {
+            ZoneShape::Rectangle => true,
+            ZoneShape::Rounded { radius } => {
+                let r = radius.min(width * 0.5).min(height);
+                let corner_x = if across < r {
+                    r
+                } else if across > width - r {
+                    width - r
+                } else {
+                    return true;
+                };
+                let corner_y = height - r;
+                depth <= corner_y || (across - corner_x).hypot(depth - corner_y) <= r
+            }
+            ZoneShape::Trapezoid { taper } => {
+                if height <= 0.0 {
+                    return true;
+                }
+                let inset = taper.min(width * 0.5) * (depth / height);
+                across >= inset && across <= width - inset
+            }
+            ZoneShape::Silhouette { radius, progress } => {
+                Silhouette::new(width, height, radius, progress).contains(across, depth)
+            }
+        }
+    }
+}
+
+impl ScreenLayout {
+    /// One zone per screen, centered on its top edge and sized from the
+    /// collapsed, peek or expanded zone config. A `view` with its own expanded
+    /// size moves the expanded zone by as much, keeping its margin around the
+    /// capsule. Zones never extend past their screen.
+    ///
+    /// With `exiting` set, the zones are grown by `hover.zone.exit_margin` on every
+    /// side but the screen edge. Testing a pointer that's already inside against
+    /// these keeps it from flickering in and out along the boundary.
+    pub fn zones(
+        &self,
+        config: &NotchConfig,
+        (stage, view): (NotchStage, NotchView),
+        exiting: bool,
+    ) -> Vec<HoverZone> {
+        let hover = &config.hover;
+        let dimensions = &config.dimensions;
+        let (width, height) = match stage {
+            NotchStage::Compact => (
+                hover.collapsed_zone_width.value,
+                hover.collapsed_zone_height.value,
+            ),
+            NotchStage::Peek => (hover.peek_zone_width.value, hover.peek_zone_height.value),
+            NotchStage::Expanded => {
+                let (width, height) = dimensions.expanded_size(view);
+                (
+                    hover.expanded_zone_width.value + width - dimensions.expanded_width.value,
+                    hover.expanded_zone_height.value + height - dimensions.expanded_height.value,
+                )
+            }
+        };
+        let margin = if exiting {
+            hover.zone.exit_margin.value.max(0.0)
+        } else {
+            0.0
+        };
+
+        self.screens
+            .iter()
+            .enumerate()
+            .map(|(index, screen)| {
+                let scale = self.scale(screen);
+                HoverZone {
+                    screen: index,
+                    rect: self.top_center(
+                        screen,
+                        (width + margin * 2.0) * scale,
+                        (height + margin) * scale,
+                    ),
+                    shape: ZoneShape::from_config(config, (stage, view), (width, height), scale),
+                    y_axis: self.y_axis,
+                }
+            })
+            .collect()
+    }
+
+    /// The zone under `(x, y)`, if any. `inside` is whether the pointer was in a
+    /// zone on the previous sample, which selects the larger exit zones.
+    pub fn zone_at(
+        &self,
+        config: &NotchConfig,
+        stage: (NotchStage, NotchView),
+        inside: bool,
+        x: f64,
+        y: f64,
+    ) -> Option<HoverZone> {
+        self.zones(config, stage, inside)
+            .into_iter()
+            .find(|zone| zone.contains(x, y))
+    }
+
+    /// Distance in points from `(x, y)` to the nearest zone's bounding rect,
+    /// or `None` without any screens.
+    pub fn distance_to_zone(
+        &self,
+        config: &NotchConfig,
+        stage: (NotchStage, NotchView),
+        x: f64,
+        y: f64,
+    ) -> Option<f64> {
+        self.zones(config, stage, false)
+            .iter()
+            .map(|zone| zone.rect.distance_to(x, y) / self.scale(&self.screens[zone.screen]))
+            .min_by(f64::total_cmp)
+    }
+
+    /// Converts a pointer position to points using the scale of the screen it's
+    /// on, for the intent tracker. Points layouts pass through unchanged.
+    pub fn to_points(&self, x: f64, y: f64) -> (f64, f64) {
+        let scale = self
+            .screens
+            .iter()
+            .find(|screen| screen.frame.contains(x, y))
+            .map_or(1.0, |screen| self.scale(screen));
+        (x / scale, y / scale)
+    }
+
+    fn scale(&self, screen: &ScreenFrame) -> f64 {
+        match self.units {
+            Units::Points => 1.0,
+            Units::Pixels => screen.scale_factor.max(f64::MIN_POSITIVE),
+        }
+    }
+
+    fn top_center(&self, screen: &ScreenFrame, width: f64, height: f64) -> Rect {
+        let frame = screen.frame;
+        let width = width.clamp(0.0, frame.width.max(0.0));
+        let height = height.clamp(0.0, frame.height.max(0.0));
+        let x = frame.center_x() - width * 0.5;
+        let y = match self.y_axis {
+            YAxis::Up => frame.max_y() - height,
+            YAxis::Down => frame.y,
+        };
+        Rect::new(x, y, width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    const COMPACT: (NotchStage, NotchView) = (NotchStage::Compact, NotchView::App);
+
+    fn layout(y_axis: YAxis, units: Units, screens: &[(f64, f64, f64, f64, f64)]) -> ScreenLayout {
+        ScreenLayout {
+            screens: screens
+                .iter()
+                .map(|&(x, y, width, height, scale_factor)| ScreenFrame {
+                    frame: Rect::new(x, y, width, height),
+                    scale_factor,
+                })
+                .collect(),
+            y_axis,
+            units,
+        }
+    }
+
+    // X11 style: a 2x primary and a 1x screen up and to the left, in pixels
+    fn pixels_down() -> ScreenLayout {
+        layout(
+            YAxis::Down,
+            Units::Pixels,
+            &[
+                (0.0, 0.0, 2880.0, 1800.0, 2.0),
+                (-1920.0, -300.0, 1920.0, 1080.0, 1.0),
+            ],
+        )
+    }
+
+    // Cocoa style: a 2x primary and a 1x screen above and to the left, in points
+    fn points_up() -> ScreenLayout {
+        layout(
+            YAxis::Up,
+            Units::Points,
+            &[
+                (0.0, 0.0, 1512.0, 982.0, 2.0),
+                (-1000.0, 982.0, 1920.0, 1080.0, 1.0),
+            ],
+        )
+    }
+
+    fn with_shape(shape: &str) -> NotchConfig {
+        let mut config = NotchConfig::default();
+        config.hover.zone.shape.value = shape.to_string();
+        config
+    }
+
+    #[test]
+    fn zones_sit_top_center_on_each_screen() {
+        let config = NotchConfig::default();
+        let settings = (NotchStage::Expanded, NotchView::Settings);
+        let narrow = layout(YAxis::Down, Units::Points, &[(0.0, 0.0, 400.0, 30.0, 1.0)]);
+        // Collapsed zones are 460x50, the settings tab 700x360 and the exit
+        // margin 8, all in points
+        let cases = [
+            (
+                "pixels, y-down",
+                pixels_down(),
+                COMPACT,
+                false,
+                vec![
+                    Rect::new(980.0, 0.0, 920.0, 100.0),
+                    Rect::new(-1190.0, -300.0, 460.0, 50.0),
+                ],
+            ),
+            (
+                "pixels, y-down, exiting",
+                pixels_down(),
+                COMPACT,
+                true,
+                vec![
+                    Rect::new(964.0, 0.0, 952.0, 116.0),
+                    Rect::new(-1198.0, -300.0, 476.0, 58.0),
+                ],
+            ),
+            (
+                "points, y-up",
+                points_up(),
+                COMPACT,
+                false,
+                vec![
+                    Rect::new(526.0, 932.0, 460.0, 50.0),
+                    Rect::new(-270.0, 2012.0, 460.0, 50.0),
+                ],
+            ),
+            (
+                "points, y-up, view size",
+                points_up(),
+                settings,
+                false,
+                vec![
+                    Rect::new(406.0, 622.0, 700.0, 360.0),
+                    Rect::new(-390.0, 1702.0, 700.0, 360.0),
+                ],
+            ),
+            (
+                "clamped to the screen",
+                narrow,
+                COMPACT,
+                false,
+                vec![Rect::new(0.0, 0.0, 400.0, 30.0)],
+            ),
+        ];
+
+        for (name, layout, stage, exiting, expected) in cases {
+            let rects: Vec<Rect> = layout
+                .zones(&config, stage, exiting)
+                .iter()
+                .map(|zone| zone.rect)
+                .collect();
+            assert_eq!(rects, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn silhouette_zones_take_the_capsule_corner_radius() {
+        let mut config = with_shape("silhouette");
+        config.hover.zone.corner_radius.value = 40.0;
+        config.dimensions.corner_radius.value = 12.0;
+        let shapes = |config: &NotchConfig| -> Vec<ZoneShape> {
+            pixels_down()
+                .zones(config, COMPACT, false)
+                .iter()
+                .map(|zone| zone.shape)
+                .collect()
+        };
+        let silhouette = |radius| ZoneShape::Silhouette {
+            radius,
+            progress: 0.0,
+        };
+
+        // The zone matches the 460x50 capsule, so the radius is the capsule's,
+        // in pixels on the 2x screen
+        assert_eq!(shapes(&config), vec![silhouette(24.0), silhouette(12.0)]);
+
+        // A zone twice the capsule's height but only 1.5 times its width
+        // grows the corners by the smaller factor
+        config.hover.collapsed_zone_width.value = 690.0;
+        config.hover.collapsed_zone_height.value = 100.0;
+        assert_eq!(shapes(&config), vec![silhouette(36.0), silhouette(18.0)]);
+    }
+
+    #[test]
+    fn zone_at_finds_the_screen_under_the_pointer() {
+        let (down, up) = (pixels_down(), points_up());
+        let (plain, rounded) = (with_shape("rectangle"), with_shape("rounded"));
+        let cases = [
+            // Primary at 2x: the zone is 100px deep, 116px when exiting
+            ("top edge", &down, &plain, false, 1440.0, 0.0, Some(0)),
+            ("bottom edge", &down, &plain, false, 1440.0, 99.0, Some(0)),
+            ("below", &down, &plain, false, 1440.0, 101.0, None),
+            ("exit margin", &down, &plain, true, 1440.0, 115.0, Some(0)),
+            ("past exit margin", &down, &plain, true, 1440.0, 117.0, None),
+            // Secondary at 1x with a negative origin
+            (
+                "negative origin",
+                &down,
+                &plain,
+                false,
+                -1189.0,
+                -251.0,
+                Some(1),
+            ),
+            ("left of it", &down, &plain, false, -1191.0, -290.0, None),
+            ("above it", &down, &plain, false, -960.0, -301.0, None),
+            // y-up: the zone hangs down from max_y
+            ("y-up top edge", &up, &plain, false, 756.0, 982.0, Some(0)),
+            ("y-up below", &up, &plain, false, 756.0, 931.0, None),
+            (
+                "y-up upper screen",
+                &up,
+                &plain,
+                false,
+                -40.0,
+                2061.0,
+                Some(1),
+            ),
+            ("y-up upper below", &up, &plain, false, -40.0, 2011.0, None),
+            // Rounded corners (16pt) are cut from the edge away from the screen
+            ("y-down corner", &down, &rounded, false, 981.0, 99.0, None),
+            (
+                "y-down top corner",
+                &down,
+                &rounded,
+                false,
+                981.0,
+                1.0,
+                Some(0),
+            ),
+            ("y-up corner", &up, &rounded, false, 527.0, 933.0, None),
+            (
+                "y-up top corner",
+                &up,
+                &rounded,
+                false,
+                527.0,
+                981.0,
+                Some(0),
+            ),
+        ];
+
+        for (name, layout, config, inside, x, y, expected) in cases {
+            let zone = layout.zone_at(config, COMPACT, inside, x, y);
+            assert_eq!(zone.map(|zone| zone.screen), expected, "{name}");
+        }
+    }
+
+    // A 200x50 zone hanging from the top of the screen at y = 0 (y-down) or
+    // y = 150 (y-up)
+    fn zone(shape: ZoneShape, y_axis: YAxis) -> HoverZone {
+        let y = match y_axis {
+            YAxis::Up => 100.0,
+            YAxis::Down => 0.0,
+        };
+        HoverZone {
+            screen: 0,
+            rect: Rect::new(0.0, y, 200.0, 50.0),
+            shape,
+            y_axis,
+        }
+    }
+
+    #[test]
+    fn shapes_cut_the_bottom_of_the_zone() {
+        let rounded = ZoneShape::Rounded { radius: 16.0 };
+        // Each side narrows by 40 over the 50 deep zone, 0.8 per point of depth
+        let trapezoid = ZoneShape::Trapezoid { taper: 40.0 };
+        let cases = [
+            // Rounded corners centered on (16, 34) and (184, 34)
+            ("rounded top corner", rounded, 1.0, 1.0, true),
+            ("rounded above the corner", rounded, 1.0, 34.0, true),
+            ("rounded left corner", rounded, 3.0, 47.0, false),
+            ("rounded inside left corner", rounded, 5.0, 45.0, true),
+            ("rounded right corner", rounded, 197.0, 47.0, false),
+            ("rounded inside right corner", rounded, 195.0, 45.0, true),
+            ("rounded bottom edge", rounded, 100.0, 50.0, true),
+            // Slanted sides at 20 in at depth 25, 40 in at the bottom
+            ("trapezoid top corners", trapezoid, 0.0, 0.0, true),
+            ("trapezoid top right", trapezoid, 200.0, 0.0, true),
+            ("trapezoid left of slant", trapezoid, 19.0, 25.0, false),
+            ("trapezoid right of left slant", trapezoid, 21.0, 25.0, true),
+            (
+                "trapezoid left of right slant",
+                trapezoid,
+                179.0,
+                25.0,
+                true,
+            ),
+            ("trapezoid right of slant", trapezoid, 181.0, 25.0, false),
+            ("trapezoid bottom corner", trapezoid, 39.0, 50.0, false),
+            ("trapezoid bottom edge", trapezoid, 41.0, 50.0, true),
+        ];
+        for (name, shape, across, depth, inside) in cases {
+            assert_eq!(
+                zone(shape, YAxis::Down).contains(across, depth),
+                inside,
+                "{name}, y-down"
+            );
+            // Same depth measured down from max_y
+            assert_eq!(
+                zone(shape, YAxis::Up).contains(across, 150.0 - depth),
+                inside,
+                "{name}, y-up"
+            );
+        }
+    }
+
+    #[test]
+    fn exit_margin_grows_every_shape() {
+        let screen = layout(
+            YAxis::Down,
+            Units::Points,
+            &[(0.0, 0.0, 1512.0, 982.0, 1.0)],
+        );
+        for shape in ["rectangle", "rounded", "trapezoid", "silhouette"] {
+            let config = with_shape(shape);
+            let entry = screen.zones(&config, COMPACT, false)[0];
+            let exit = screen.zones(&config, COMPACT, true)[0];
+            // 8pt left, right and below the 460x50 zone, with the same shape
+            assert_eq!(entry.rect, Rect::new(526.0, 0.0, 460.0, 50.0), "{shape}");
+            assert_eq!(exit.rect, Rect::new(518.0, 0.0, 476.0, 58.0), "{shape}");
+            assert_eq!(exit.shape, entry.shape, "{shape}");
+            // Past the entry zone's bottom, and its sides near the top where the
+            // trapezoid and silhouette are at their widest
+            for (x, y) in [(756.0, 54.0), (522.0, 1.0), (990.0, 1.0)] {
+                assert!(!entry.contains(x, y), "{shape} entry at {x}, {y}");
+                assert!(exit.contains(x, y), "{shape} exit at {x}, {y}");
+            }
+        }
+    }
+
+    // Random desktops for the property tests: a primary at the origin and up
+    // to three more screens, each placed left of, above, right of or below an
+    // earlier one so origins go negative, with mixed scale factors
+    #[derive(Clone, Debug)]
+    struct Desktop(ScreenLayout);
+
+    impl Arbitrary for Desktop {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let mut screens: Vec<ScreenFrame> = Vec::new();
+            for _ in 0..1 + usize::arbitrary(g) % 4 {
+                let width = 320.0 + (u16::arbitrary(g) % 5000) as f64;
+                let height = 240.0 + (u16::arbitrary(g) % 3000) as f64;
+                let frame = match screens.get(usize::arbitrary(g) % screens.len().max(1)) {
+                    None => Rect::new(0.0, 0.0, width, height),
+                    Some(next_to) => {
+                        let next_to = next_to.frame;
+                        let slide = (u16::arbitrary(g) % 2000) as f64 - 1000.0;
+                        match *g.choose(&["left", "above", "right", "below"]).unwrap() {
+                            "left" => {
+                                Rect::new(next_to.x - width, next_to.y + slide, width, height)
+                            }
+                            "above" => {
+                                Rect::new(next_to.x + slide, next_to.y - height, width, height)
+                            }
+                            "right" => Rect::new(next_to.max_x(), next_to.y + slide, width, height),
+                            _ => Rect::new(next_to.x + slide, next_to.max_y(), width, height),
+                        }
+                    }
+                };
+                screens.push(ScreenFrame {
+                    frame,
+                    scale_factor: *g.choose(&[1.0, 1.25, 1.5, 2.0, 3.0]).unwrap(),
+                });
+            }
+            Desktop(ScreenLayout {
+                screens,
+                y_axis: *g.choose(&[YAxis::Up, YAxis::Down]).unwrap(),
+                units: *g.choose(&[Units::Points, Units::Pixels]).unwrap(),
+            })
+        }
+    }
+
+    // Zone sizes (some wider or taller than a screen), margin, shape and stage
+    #[derive(Clone, Debug)]
+    struct Zones(NotchConfig, (NotchStage, NotchView));
+
+    impl Arbitrary for Zones {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let shape = g.choose(&["rectangle", "rounded", "trapezoid", "silhouette"]);
+            let mut config = with_shape(shape.unwrap());
+            let hover = &mut config.hover;
+            hover.collapsed_zone_width.value = (u16::arbitrary(g) % 6000) as f64;
+            hover.collapsed_zone_height.value = (u16::arbitrary(g) % 600) as f64;
+            hover.peek_zone_width.value = (u16::arbitrary(g) % 1000) as f64;
+            hover.peek_zone_height.value = (u16::arbitrary(g) % 200) as f64;
+            hover.zone.exit_margin.value = (u8::arbitrary(g) % 64) as f64;
+            hover.zone.corner_radius.value = (u8::arbitrary(g) % 64) as f64;
+            hover.zone.taper.value = (u8::arbitrary(g) % 128) as f64;
+            let stage = *g
+                .choose(&[NotchStage::Compact, NotchStage::Peek, NotchStage::Expanded])
+                .unwrap();
+            let view = *g.choose(&[NotchView::App, NotchView::Settings]).unwrap();
+            Zones(config, (stage, view))
+        }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    fn within(inner: &Rect, outer: &Rect) -> bool {
+        inner.x >= outer.x - 1e-6
+            && inner.max_x() <= outer.max_x() + 1e-6
+            && inner.y >= outer.y - 1e-6
+            && inner.max_y() <= outer.max_y() + 1e-6
+    }
+
+    #[test]
+    fn zones_stay_on_their_screen_centered_at_the_top() {
+        fn property(Desktop(layout): Desktop, Zones(config, stage): Zones, exiting: bool) -> bool {
+            let zones = layout.zones(&config, stage, exiting);
+            zones.len() == layout.screens.len()
+                && zones.iter().enumerate().all(|(index, zone)| {
+                    let frame = layout.screens[index].frame;
+                    let top = match layout.y_axis {
+                        YAxis::Up => close(zone.rect.max_y(), frame.max_y()),
+                        YAxis::Down => close(zone.rect.y, frame.y),
+                    };
+                    zone.screen == index
+                        && within(&zone.rect, &frame)
+                        && close(zone.rect.center_x(), frame.center_x())
+                        && top
+                })
+        }
+        quickcheck(property as fn(Desktop, Zones, bool) -> bool);
+    }
+
+    #[test]
+    fn exit_zones_cover_the_entry_zones() {
+        fn property(Desktop(layout): Desktop, Zones(config, stage): Zones) -> bool {
+            let entry = layout.zones(&config, stage, false);
+            let exit = layout.zones(&config, stage, true);
+            entry.iter().zip(&exit).all(|(entry, exit)| {
+                // Every point of the entry shape, sampled on a grid, is in the
+                // exit shape too
+                let Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                } = entry.rect;
+                within(&entry.rect, &exit.rect)
+                    && (0..=16).all(|i| {
+                        (0..=16).all(|j| {
+                            let (px, py) =
+                                (x + width * i as f64 / 16.0, y + height * j as f64 / 16.0);
+                            !entry.contains(px, py) || exit.contains(px, py)
+                        })
+                    })
+            })
+        }
+        quickcheck(property as fn(Desktop, Zones) -> bool);
+    }
+
+    #[test]
+    fn zones_move_with_their_screen() {
+        fn property(
+            Desktop(layout): Desktop,
+            Zones(config, stage): Zones,
+            moved: usize,
+            by: (i16, i16),
+        ) -> bool {
+            let moved = moved % layout.screens.len();
+            let (dx, dy) = (by.0 as f64, by.1 as f64);
+            let mut shifted = layout.clone();
+            shifted.screens[moved].frame.x += dx;
+            shifted.screens[moved].frame.y += dy;
+
+            let before = layout.zones(&config, stage, false);
+            let after = shifted.zones(&config, stage, false);
+            before.iter().zip(&after).all(|(before, after)| {
+                let (dx, dy) = if before.screen == moved {
+                    (dx, dy)
+                } else {
+                    (0.0, 0.0)
+                };
+                close(after.rect.x, before.rect.x + dx)
+                    && close(after.rect.y, before.rect.y + dy)
+                    && close(after.rect.width, before.rect.width)
+                    && close(after.rect.height, before.rect.height)
+                    && after.shape == before.shape
+            })
+        }
+        quickcheck(property as fn(Desktop, Zones, usize, (i16, i16)) -> bool);
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How much pointer history the speed estimate looks at.
+pub const TRAIL_WINDOW: Duration = Duration::from_millis(120);
+
+// The pointer counts as braking when its recent speed drops below this fraction
+// of the speed earlier in the window.
+const DECELERATION_RATIO: f64 = 0.5;
+
+/// Recent pointer positions, used to tell a deliberate move into the zone from a
+/// fast pass across it (on the way to a menu bar item, say).
+#[derive(Debug, Default)]
+pub struct PointerTrail {
+    samples: VecDeque<(Instant, f64, f64)>,
+}
+
+impl PointerTrail {
+    pub fn push(&mut self, at: Instant, x: f64, y: f64) {
+        self.samples.push_back((at, x, y));
+        // Keep one sample older than the window so its start can be measured.
+        while self.samples.len() > 2
+            && self
+                .samples
+                .get(1)
+                .is_some_and(|&(t, _, _)| at.saturating_duration_since(t) > TRAIL_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Most recent position.
+    pub fn last(&self) -> Option<(f64, f64)> {
+        self.samples.back().map(|&(_, x, y)| (x, y))
+    }
+
+    /// Average speed in points per second across the window; 0 without movement data.
+    pub fn speed(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => speed_between(first, last),
+            _ => 0.0,
+        }
+    }
+
+    /// Whether the second half of the window is markedly slower than the first.
+    pub fn decelerating(&self) -> bool {
+        if self.samples.len() < 3 {
+            return false;
+        }
+        let mid = self.samples.len() / 2;
+        let earlier = speed_between(&self.samples[0], &self.samples[mid]);
+        let recent = speed_between(&self.samples[mid], &self.samples[self.samples.len() - 1]);
+        earlier > 0.0 && recent < earlier * DECELERATION_RATIO
+    }
+
+    /// Dwelling (at or under `threshold`) or braking counts as intent.
+    /// A threshold of 0 turns the check off.
+    pub fn shows_intent(&self, threshold: f64) -> bool {
+        threshold <= 0.0 || self.speed() <= threshold || self.decelerating()
+    }
+}
+
+fn speed_between(a: &(Instant, f64, f64), b: &(Instant, f64, f64)) -> f64 {
+    let dt = b.0.saturating_duration_since(a.0).as_secs_f64();
+    if dt <= 0.0 {
+        return 0.0;
+    }
+    (b.1 - a.1).hypot(b.2 - a.2) / dt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotchConfig;
+
+    // 16ms apart, nine samples fill the window plus the one kept before it
+    const STEP: Duration = Duration::from_millis(16);
+
+    fn threshold() -> f64 {
+        NotchConfig::default().hover.velocity_threshold.value
+    }
+
+    // A horizontal move: each `(speed, steps)` segment runs at `speed` points
+    // per second for `steps` samples
+    fn trace(segments: &[(f64, usize)]) -> PointerTrail {
+        let mut trail = PointerTrail::default();
+        let mut at = Instant::now();
+        let mut x = 0.0;
+        trail.push(at, x, 0.0);
+        for &(speed, steps) in segments {
+            for _ in 0..steps {
+                at += STEP;
+                x += speed * STEP.as_secs_f64();
+                trail.push(at, x, 0.0);
+            }
+        }
+        trail
+    }
+
+    #[test]
+    fn default_threshold_is_1200() {
+        assert_eq!(threshold(), 1200.0);
+    }
+
+    #[test]
+    fn fly_past_shows_no_intent() {
+        let trail = trace(&[(3000.0, 60)]);
+        assert!((trail.speed() - 3000.0).abs() < 1e-6);
+        assert!(!trail.decelerating());
+        assert!(!trail.shows_intent(threshold()));
+        // Just over the threshold still counts as passing through
+        assert!(!trace(&[(1250.0, 8)]).shows_intent(threshold()));
+    }
+
+    #[test]
+    fn dwelling_shows_intent() {
+        let trail = trace(&[(3000.0, 4), (0.0, 20)]);
+        assert_eq!(trail.speed(), 0.0);
+        assert!(trail.shows_intent(threshold()));
+        // Slow drifting, and moving right at the threshold
+        assert!(trace(&[(120.0, 8)]).shows_intent(threshold()));
+        assert!(trace(&[(1200.0, 8)]).shows_intent(threshold()));
+    }
+
+    #[test]
+    fn braking_into_the_zone_shows_intent() {
+        let earlier = 3000.0;
+        let braking = trace(&[(earlier, 4), (earlier * DECELERATION_RATIO * 0.8, 4)]);
+        // Still fast on average, so only the braking counts
+        assert!(braking.speed() > threshold());
+        assert!(braking.decelerating());
+        assert!(braking.shows_intent(threshold()));
+
+        let easing = trace(&[(earlier, 4), (earlier * DECELERATION_RATIO * 1.2, 4)]);
+        assert!(easing.speed() > threshold());
+        assert!(!easing.decelerating());
+        assert!(!easing.shows_intent(threshold()));
+    }
+
+    #[test]
+    fn old_samples_leave_the_window() {
+        // Braked long ago, then sped up again
+        let trail = trace(&[(3000.0, 4), (300.0, 4), (3000.0, 20)]);
+        assert!(!trail.decelerating());
+        assert!(!trail.shows_intent(threshold()));
+    }
+
+    #[test]
+    fn zero_threshold_always_shows_intent() {
+        assert!(trace(&[(5000.0, 8)]).shows_intent(0.0));
+        assert!(PointerTrail::default().shows_intent(threshold()));
+    }
+}
@@ -0,0 +1,250 @@
+use super::geometry::{Rect, ScreenFrame, ScreenLayout, Units, YAxis};
+use super::schedule::MONITOR_HEARTBEAT;
+use super::{surface, HoverDebounce, HoverMonitor, HoverSwitch, Modifiers, PollScheduler};
+use crate::config::NotchConfig;
+use crate::state::SharedStage;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Monitor};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{ConnectionExt, KeyButMask, Window};
+use x11rb::rust_connection::RustConnection;
+
+// Monitors come and go rarely; re-reading them every poll would mean a round
+// trip to the main thread each time.
+const LAYOUT_REFRESH: Duration = Duration::from_secs(2);
+
+/// Pointer position straight from the X server, in root window pixels. Works on
+/// any X display including Xvfb and XWayland, without a window of our own, so
+/// it can be driven from tests with `xdotool mousemove`.
+pub struct X11Pointer {
+    conn: RustConnection,
+    root: Window,
+}
+
+impl X11Pointer {
+    /// Connects to `display`, or to `$DISPLAY` when `None`.
+    pub fn connect(display: Option<&str>) -> Result<Self, String> {
+        let (conn, screen) =
+            x11rb::connect(display).map_err(|e| format!("can't connect to X server: {}", e))?;
+        let root = conn
+            .setup()
+            .roots
+            .get(screen)
+            .map(|s| s.root)
+            .ok_or("X server has no root window")?;
+        Ok(Self { conn, root })
+    }
+
+    /// Pointer position and the modifier keys held.
+    pub fn position(&self) -> Option<(f64, f64, Modifiers)> {
+        let reply = self.conn.query_pointer(self.root).ok()?.reply().ok()?;
+        let modifiers = Modifiers {
+            shift: reply.mask.contains(KeyButMask::SHIFT),
+            control: reply.mask.contains(KeyButMask::CONTROL),
+            alt: reply.mask.contains(KeyButMask::MOD1),
+            meta: reply.mask.contains(KeyButMask::MOD4),
+        };
+        Some((reply.root_x as f64, reply.root_y as f64, modifiers))
+    }
+}
+
+/// Tauri's monitors as a y-down layout in physical pixels, the space X11 reports
+/// the pointer in.
+pub fn screen_layout(monitors: &[Monitor]) -> ScreenLayout {
+    let screens = monitors
+        .iter()
+        .map(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+            ScreenFrame {
+                frame: Rect::new(
+                    position.x as f64,
+                    position.y as f64,
+                    size.width as f64,
+                    size.height as f64,
+                ),
+                scale_factor: monitor.scale_factor(),
+            }
+        })
+        .collect();
+    ScreenLayout {
+        screens,
+        y_axis: YAxis::Down,
+        units: Units::Pixels,
+    }
+}
+
+/// Starts hover detection. Wayland hides the global pointer position, so there
+/// it comes from the capsule window's own enter/leave events; everywhere else
+/// (X11, XWayland-only sessions) the X server is polled, at a rate set by `poll`.
+pub fn start(
+    app: &AppHandle,
+    zones: Arc<SharedStage>,
+    debounce: Arc<HoverDebounce>,
+    poll: Arc<Mutex<PollScheduler>>,
+    monitor: &HoverMonitor,
+) {
+    monitor.add_thread(debounce.spawn_timer(app));
+
+    if let Some(window) = app.get_webview_window("notch-capsule") {
+        if surface::is_wayland(&window) {
+            match surface::watch(app, &window, debounce, monitor.switch()) {
+                Ok(()) => {
+                    eprintln!("Hover: tracking capsule surface enter/leave (Wayland)");
+                    monitor.set_backend("wayland");
+                }
+                Err(e) => eprintln!("Hover detection unavailable: {}", e),
+            }
+            return;
+        }
+    }
+
+    match X11Pointer::connect(None) {
+        Ok(pointer) => {
+            eprintln!("Hover: polling X11 pointer");
+            let switch = monitor.switch();
+            monitor.add_thread(spawn_poller(
+                app.clone(),
+                pointer,
+                zones,
+                debounce,
+                poll,
+                switch,
+            ));
+            monitor.set_backend("x11");
+        }
+        Err(e) => eprintln!("Hover detection unavailable: {}", e),
+    }
+}
+
+fn spawn_poller(
+    app: AppHandle,
+    pointer: X11Pointer,
+    zones: Arc<SharedStage>,
+    debounce: Arc<HoverDebounce>,
+    poll: Arc<Mutex<PollScheduler>>,
+    switch: Arc<HoverSwitch>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut layout = None;
+        let mut refreshed = Instant::now();
+        let mut was_inside = false;
+        let mut interval = poll
+            .lock()
+            .map(|p| p.interval())
+            .unwrap_or(MONITOR_HEARTBEAT);
+        while switch.sleep(interval) {
+            if switch.is_paused() {
+                // Re-enter cleanly on resume, the debounce has been reset
+                was_inside = false;
+                continue;
+            }
+            if layout.is_none() || refreshed.elapsed() >= LAYOUT_REFRESH {
+                if let Ok(monitors) = app.available_monitors() {
+                    layout = Some(screen_layout(&monitors));
+                }
+                refreshed = Instant::now();
+                // The dispatch can outlast a shutdown, which doesn't wait for it
+                if switch.is_stopped() {
+                    break;
+                }
+            }
+            let (Some(layout), Some((x, y, modifiers))) = (&layout, pointer.position()) else {
+                continue;
+            };
+
+            let config = NotchConfig::get();
+            let stage = zones.load();
+            if let Ok(mut poll) = poll.lock() {
+                let distance = layout.distance_to_zone(config, stage, x, y);
+                interval = poll.record_poll(Instant::now(), (x, y), distance);
+            }
+
+            let zone = layout.zone_at(config, stage, was_inside, x, y);
+            let inside = zone.is_some();
+            if inside != was_inside {
+                was_inside = inside;
+                eprintln!(
+                    "Mouse hover state changed: inside={} screen={:?}",
+                    inside,
+                    zone.map(|z| z.screen)
+                );
+                let _ = app.emit("notch-hover", serde_json::json!({ "inside": inside }));
+            }
+            let (x, y) = layout.to_points(x, y);
+            debounce.feed(&app, x, y, inside, modifiers);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{NotchStage, NotchView};
+    use std::process::{Child, Command};
+
+    const DISPLAY: &str = ":97";
+
+    // Kills the server when the test ends, pass or fail
+    struct Xvfb(Child);
+
+    impl Drop for Xvfb {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    fn xvfb() -> (Xvfb, X11Pointer) {
+        let server = Command::new("Xvfb")
+            .args([DISPLAY, "-screen", "0", "1280x800x24", "-nolisten", "tcp"])
+            .spawn()
+            .map(Xvfb)
+            .expect("Xvfb not installed");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match X11Pointer::connect(Some(DISPLAY)) {
+                Ok(pointer) => return (server, pointer),
+                Err(e) if Instant::now() >= deadline => panic!("{}", e),
+                Err(_) => thread::sleep(Duration::from_millis(50)),
+            }
+        }
+    }
+
+    // What `xdotool mousemove` does, without needing it installed
+    fn move_to(pointer: &X11Pointer, x: i16, y: i16) {
+        pointer
+            .conn
+            .warp_pointer(x11rb::NONE, pointer.root, 0, 0, 0, 0, x, y)
+            .unwrap()
+            .check()
+            .unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb; run with `cargo test -- --ignored`"]
+    fn polls_the_pointer_under_xvfb() {
+        let (_server, pointer) = xvfb();
+        let layout = ScreenLayout {
+            screens: vec![ScreenFrame {
+                frame: Rect::new(0.0, 0.0, 1280.0, 800.0),
+                scale_factor: 1.0,
+            }],
+            y_axis: YAxis::Down,
+            units: Units::Pixels,
+        };
+        let config = NotchConfig::default();
+        let stage = (NotchStage::Compact, NotchView::App);
+
+        for (x, y, inside) in [(640, 10, true), (640, 400, false), (420, 0, true)] {
+            move_to(&pointer, x, y);
+            let (px, py, modifiers) = pointer.position().expect("no pointer");
+            assert_eq!((px, py), (x as f64, y as f64));
+            assert_eq!(modifiers, Modifiers::default());
+            let zone = layout.zone_at(&config, stage, false, px, py);
+            assert_eq!(zone.is_some(), inside, "at {x},{y}");
+        }
+    }
+}
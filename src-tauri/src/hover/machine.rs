@@ -0,0 +1,602 @@
+use super::intent::PointerTrail;
+use super::{ActivationMode, Modifiers};
+use crate::config::NotchConfig;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Source of the current time, so the debounce can run against a fake clock.
+pub trait Clock: Send {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoverPhase {
+    Idle,
+    /// Pointer is in the zone; expands at `deadline` unless it leaves first.
+    PendingExpand {
+        deadline: Instant,
+    },
+    Expanded,
+    /// Pointer left the zone; collapses at `deadline` unless it comes back first.
+    PendingCollapse {
+        deadline: Instant,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoverDecision {
+    Expand,
+    Collapse,
+}
+
+impl HoverDecision {
+    /// Event the decision is announced with.
+    pub fn event(self) -> &'static str {
+        match self {
+            HoverDecision::Expand => "notch-should-expand",
+            HoverDecision::Collapse => "notch-should-collapse",
+        }
+    }
+}
+
+/// Debounces raw inside/outside pointer samples into expand/collapse decisions
+/// (Idle → PendingExpand → Expanded → PendingCollapse → Idle).
+///
+/// Both directions are edge-triggered: the pointer has to enter or leave the zone.
+/// Expanding or collapsing from elsewhere (a click, [`force`](Self::force)) is left
+/// alone until the pointer actually crosses the zone boundary.
+///
+/// An entry only arms the expand delay once the pointer shows intent, i.e. it
+/// dwells under `velocity_threshold` or visibly brakes inside the zone. Fast
+/// passes across the top of the screen never leave Idle.
+///
+/// The activation mode decides whether hovering arms at all (see
+/// [`ActivationMode`]); in click modes [`click`](Self::click) expands right away.
+/// With a modifier required, releasing it counts as leaving the zone and
+/// pressing it again as re-entering.
+/// While [pinned](Self::with_pinned) the pointer never collapses it.
+pub struct HoverMachine<C: Clock = SystemClock> {
+    clock: C,
+    phase: HoverPhase,
+    expand_delay: Duration,
+    collapse_delay: Duration,
+    velocity_threshold: f64,
+    activation: ActivationMode,
+    pinned: Arc<AtomicBool>,
+    trail: PointerTrail,
+    was_inside: bool,
+    // Entered the zone but hasn't shown intent yet.
+    entry_pending: bool,
+}
+
+impl HoverMachine<SystemClock> {
+    pub fn from_config(config: &NotchConfig) -> Self {
+        let hover = &config.hover;
+        Self::new(
+            SystemClock,
+            Duration::from_millis(hover.expand_delay_ms.value),
+            Duration::from_millis(hover.collapse_delay_ms.value),
+            hover.velocity_threshold.value,
+        )
+        .with_activation(ActivationMode::from_config(&config.activation))
+    }
+}
+
+impl<C: Clock> HoverMachine<C> {
+    /// `velocity_threshold` is in points per second; 0 arms on every entry.
+    pub fn new(
+        clock: C,
+        expand_delay: Duration,
+        collapse_delay: Duration,
+        velocity_threshold: f64,
+    ) -> Self {
+        Self {
+            clock,
+            phase: HoverPhase::Idle,
+            expand_delay,
+            collapse_delay,
+            velocity_threshold,
+            activation: ActivationMode::Hover,
+            pinned: Arc::default(),
+            trail: PointerTrail::default(),
+            was_inside: false,
+            entry_pending: false,
+        }
+    }
+
+    /// Replaces the default hover activation.
+    pub fn with_activation(mut self, activation: ActivationMode) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    /// Reads pinning from `pinned`, normally [`NotchStore::pinned`]. The
+    /// machine never writes it; call [`pin_changed`](Self::pin_changed) after
+    /// it changes.
+    ///
+    /// [`NotchStore::pinned`]: crate::state::NotchStore::pinned
+    pub fn with_pinned(mut self, pinned: Arc<AtomicBool>) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        matches!(
+            self.phase,
+            HoverPhase::Expanded | HoverPhase::PendingCollapse { .. }
+        )
+    }
+
+    /// When the pending phase resolves, if one is pending. Callers without a
+    /// steady stream of samples should [`tick`](Self::tick) then.
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.phase {
+            HoverPhase::PendingExpand { deadline } | HoverPhase::PendingCollapse { deadline } => {
+                Some(deadline)
+            }
+            HoverPhase::Idle | HoverPhase::Expanded => None,
+        }
+    }
+
+    /// Feeds one pointer sample, with the position in points.
+    pub fn sample(
+        &mut self,
+        x: f64,
+        y: f64,
+        inside: bool,
+        modifiers: Modifiers,
+    ) -> Option<HoverDecision> {
+        let now = self.clock.now();
+        self.trail.push(now, x, y);
+        let inside = inside && self.activation.counts_inside(modifiers);
+        let crossed = inside != self.was_inside;
+        self.was_inside = inside;
+        if crossed {
+            self.entry_pending = inside;
+        }
+
+        self.phase = match (self.phase, inside) {
+            (HoverPhase::Idle, true)
+                if self.entry_pending
+                    && self.activation.hover_expands(modifiers)
+                    && self.trail.shows_intent(self.velocity_threshold) =>
+            {
+                self.entry_pending = false;
+                HoverPhase::PendingExpand {
+                    deadline: now + self.expand_delay,
+                }
+            }
+            (HoverPhase::PendingExpand { .. }, false) => HoverPhase::Idle,
+            (HoverPhase::Expanded, false) if crossed && !self.is_pinned() => {
+                HoverPhase::PendingCollapse {
+                    deadline: now + self.collapse_delay,
+                }
+            }
+            (HoverPhase::PendingCollapse { .. }, true) => HoverPhase::Expanded,
+            (phase, _) => phase,
+        };
+        self.resolve(now)
+    }
+
+    /// A crossing reported without coordinates (e.g. by the webview). It reuses
+    /// the last known position, so it doesn't register as movement.
+    pub fn sample_crossing(&mut self, inside: bool, modifiers: Modifiers) -> Option<HoverDecision> {
+        let (x, y) = self.trail.last().unwrap_or_default();
+        self.sample(x, y, inside, modifiers)
+    }
+
+    /// The capsule was clicked. Expands immediately in click and hybrid modes;
+    /// clicks on an open capsule are left to its content.
+    pub fn click(&mut self) -> Option<HoverDecision> {
+        if !self.activation.click_expands() || self.is_expanded() {
+            return None;
+        }
+        self.entry_pending = false;
+        self.phase = HoverPhase::Expanded;
+        Some(HoverDecision::Expand)
+    }
+
+    /// Resolves a pending phase whose deadline has passed, without a new sample.
+    pub fn tick(&mut self) -> Option<HoverDecision> {
+        let now = self.clock.now();
+        self.resolve(now)
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.load(Ordering::Relaxed)
+    }
+
+    /// Catches up with a change to the pinned flag. Pinning holds the capsule
+    /// open: it expands if it isn't already and hover never collapses it.
+    /// Unpinning with the pointer outside the zone starts the usual collapse
+    /// delay.
+    pub fn pin_changed(&mut self) -> Option<HoverDecision> {
+        if self.is_pinned() {
+            let was_expanded = self.is_expanded();
+            self.entry_pending = false;
+            self.phase = HoverPhase::Expanded;
+            (!was_expanded).then_some(HoverDecision::Expand)
+        } else {
+            if self.phase == HoverPhase::Expanded && !self.was_inside {
+                self.phase = HoverPhase::PendingCollapse {
+                    deadline: self.clock.now() + self.collapse_delay,
+                };
+            }
+            None
+        }
+    }
+
+    /// Syncs with an expand/collapse that happened outside the machine (UI
+    /// buttons, keyboard). Echoes of the machine's own decisions are no-ops, so a
+    /// collapse that's already pending survives the UI confirming the expand.
+    pub fn force(&mut self, expanded: bool) {
+        self.entry_pending = false;
+        if self.is_expanded() != expanded {
+            self.phase = if expanded {
+                HoverPhase::Expanded
+            } else {
+                HoverPhase::Idle
+            };
+        }
+    }
+
+    /// Drops a pending phase back to where the UI already is and forgets the
+    /// pointer, so the next sample inside the zone counts as a fresh entry.
+    pub fn reset(&mut self) {
+        self.phase = match self.phase {
+            HoverPhase::PendingExpand { .. } => HoverPhase::Idle,
+            HoverPhase::PendingCollapse { .. } => HoverPhase::Expanded,
+            phase => phase,
+        };
+        self.trail = PointerTrail::default();
+        self.was_inside = false;
+        self.entry_pending = false;
+    }
+
+    fn resolve(&mut self, now: Instant) -> Option<HoverDecision> {
+        match self.phase {
+            HoverPhase::PendingExpand { deadline } if now >= deadline => {
+                self.phase = HoverPhase::Expanded;
+                Some(HoverDecision::Expand)
+            }
+            HoverPhase::PendingCollapse { deadline } if now >= deadline => {
+                self.phase = HoverPhase::Idle;
+                Some(HoverDecision::Collapse)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hover::activation::Modifier;
+    use std::sync::Mutex;
+
+    const EXPAND: Duration = Duration::from_millis(100);
+    const COLLAPSE: Duration = Duration::from_millis(200);
+
+    // Shared with the test so it can move time while the machine owns a copy
+    #[derive(Clone)]
+    struct FakeClock {
+        start: Instant,
+        offset: Arc<Mutex<Duration>>,
+    }
+
+    impl FakeClock {
+        fn set_ms(&self, ms: u64) {
+            *self.offset.lock().unwrap() = Duration::from_millis(ms);
+        }
+
+        fn at_ms(&self, ms: u64) -> Instant {
+            self.start + Duration::from_millis(ms)
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.start + *self.offset.lock().unwrap()
+        }
+    }
+
+    // A threshold of 0 arms on every entry; intent has its own tests
+    fn machine() -> (HoverMachine<FakeClock>, FakeClock) {
+        let clock = FakeClock {
+            start: Instant::now(),
+            offset: Arc::default(),
+        };
+        (
+            HoverMachine::new(clock.clone(), EXPAND, COLLAPSE, 0.0),
+            clock,
+        )
+    }
+
+    fn with_alt(machine: &mut HoverMachine<FakeClock>, alt: bool) -> Option<HoverDecision> {
+        let modifiers = Modifiers {
+            alt,
+            ..Modifiers::default()
+        };
+        machine.sample(10.0, 5.0, true, modifiers)
+    }
+
+    fn alt_machine() -> (HoverMachine<FakeClock>, FakeClock) {
+        let (machine, clock) = machine();
+        let mode = ActivationMode::HoverWithModifier(Modifier::Alt);
+        (machine.with_activation(mode), clock)
+    }
+
+    fn inside(machine: &mut HoverMachine<FakeClock>) -> Option<HoverDecision> {
+        machine.sample(10.0, 5.0, true, Modifiers::default())
+    }
+
+    fn outside(machine: &mut HoverMachine<FakeClock>) -> Option<HoverDecision> {
+        machine.sample(10.0, 80.0, false, Modifiers::default())
+    }
+
+    // Enters at 0ms and expands at 100ms
+    fn expanded() -> (HoverMachine<FakeClock>, FakeClock) {
+        let (mut machine, clock) = machine();
+        inside(&mut machine);
+        clock.set_ms(100);
+        assert_eq!(machine.tick(), Some(HoverDecision::Expand));
+        (machine, clock)
+    }
+
+    #[test]
+    fn entry_expands_after_the_delay() {
+        let (mut machine, clock) = machine();
+        assert_eq!(inside(&mut machine), None);
+        assert_eq!(
+            machine.phase,
+            HoverPhase::PendingExpand {
+                deadline: clock.at_ms(100)
+            }
+        );
+        assert!(!machine.is_expanded());
+
+        clock.set_ms(99);
+        assert_eq!(machine.tick(), None);
+        clock.set_ms(100);
+        assert_eq!(machine.tick(), Some(HoverDecision::Expand));
+        assert_eq!(machine.phase, HoverPhase::Expanded);
+        assert_eq!(machine.deadline(), None);
+    }
+
+    #[test]
+    fn samples_resolve_a_passed_deadline() {
+        let (mut machine, clock) = machine();
+        inside(&mut machine);
+        clock.set_ms(150);
+        assert_eq!(inside(&mut machine), Some(HoverDecision::Expand));
+        clock.set_ms(300);
+        assert_eq!(inside(&mut machine), None);
+    }
+
+    #[test]
+    fn leaving_cancels_a_pending_expand() {
+        let (mut machine, clock) = machine();
+        inside(&mut machine);
+        clock.set_ms(50);
+        assert_eq!(outside(&mut machine), None);
+        assert_eq!(machine.phase, HoverPhase::Idle);
+        assert_eq!(machine.deadline(), None);
+
+        clock.set_ms(500);
+        assert_eq!(machine.tick(), None);
+        assert!(!machine.is_expanded());
+    }
+
+    #[test]
+    fn leaving_collapses_after_the_delay() {
+        let (mut machine, clock) = expanded();
+        clock.set_ms(400);
+        assert_eq!(outside(&mut machine), None);
+        assert_eq!(
+            machine.phase,
+            HoverPhase::PendingCollapse {
+                deadline: clock.at_ms(600)
+            }
+        );
+        assert!(machine.is_expanded());
+
+        clock.set_ms(599);
+        assert_eq!(machine.tick(), None);
+        clock.set_ms(600);
+        assert_eq!(machine.tick(), Some(HoverDecision::Collapse));
+        assert_eq!(machine.phase, HoverPhase::Idle);
+        assert_eq!(machine.tick(), None);
+    }
+
+    #[test]
+    fn reentering_cancels_a_pending_collapse() {
+        let (mut machine, clock) = expanded();
+        clock.set_ms(400);
+        outside(&mut machine);
+        clock.set_ms(500);
+        assert_eq!(inside(&mut machine), None);
+        assert_eq!(machine.phase, HoverPhase::Expanded);
+
+        clock.set_ms(1000);
+        assert_eq!(machine.tick(), None);
+        assert!(machine.is_expanded());
+    }
+
+    #[test]
+    fn staying_inside_only_expands_once() {
+        let (mut machine, clock) = expanded();
+        machine.force(false);
+        clock.set_ms(1000);
+        // No crossing since the entry, so the UI's collapse stands
+        assert_eq!(inside(&mut machine), None);
+        clock.set_ms(2000);
+        assert_eq!(machine.tick(), None);
+        assert_eq!(machine.phase, HoverPhase::Idle);
+    }
+
+    #[test]
+    fn force_expand_waits_for_a_crossing_to_collapse() {
+        let (mut machine, clock) = machine();
+        machine.force(true);
+        assert_eq!(machine.phase, HoverPhase::Expanded);
+        // Never inside, so staying outside isn't a leave
+        assert_eq!(outside(&mut machine), None);
+        clock.set_ms(1000);
+        assert_eq!(machine.tick(), None);
+        assert_eq!(machine.phase, HoverPhase::Expanded);
+    }
+
+    #[test]
+    fn force_echo_keeps_a_pending_collapse() {
+        let (mut machine, clock) = expanded();
+        clock.set_ms(400);
+        outside(&mut machine);
+        machine.force(true);
+        clock.set_ms(600);
+        assert_eq!(machine.tick(), Some(HoverDecision::Collapse));
+    }
+
+    #[test]
+    fn force_echo_keeps_a_pending_expand() {
+        let (mut machine, clock) = machine();
+        inside(&mut machine);
+        machine.force(false);
+        clock.set_ms(100);
+        assert_eq!(machine.tick(), Some(HoverDecision::Expand));
+    }
+
+    #[test]
+    fn force_collapse_drops_a_pending_collapse() {
+        let (mut machine, clock) = expanded();
+        clock.set_ms(400);
+        outside(&mut machine);
+        machine.force(false);
+        assert_eq!(machine.phase, HoverPhase::Idle);
+        clock.set_ms(600);
+        assert_eq!(machine.tick(), None);
+    }
+
+    // Flips the store's flag the way `NotchStore::update` does, then tells the machine
+    fn pin(
+        machine: &mut HoverMachine<FakeClock>,
+        flag: &AtomicBool,
+        pinned: bool,
+    ) -> Option<HoverDecision> {
+        flag.store(pinned, Ordering::Relaxed);
+        machine.pin_changed()
+    }
+
+    #[test]
+    fn pinning_holds_the_capsule_open() {
+        let (machine, clock) = machine();
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut machine = machine.with_pinned(flag.clone());
+        assert_eq!(pin(&mut machine, &flag, true), Some(HoverDecision::Expand));
+        assert!(machine.is_pinned());
+
+        inside(&mut machine);
+        clock.set_ms(100);
+        assert_eq!(outside(&mut machine), None);
+        clock.set_ms(1000);
+        assert_eq!(machine.tick(), None);
+        assert_eq!(machine.phase, HoverPhase::Expanded);
+
+        // Unpinned outside the zone, it collapses after the usual delay
+        assert_eq!(pin(&mut machine, &flag, false), None);
+        assert_eq!(
+            machine.phase,
+            HoverPhase::PendingCollapse {
+                deadline: clock.at_ms(1200)
+            }
+        );
+        clock.set_ms(1200);
+        assert_eq!(machine.tick(), Some(HoverDecision::Collapse));
+    }
+
+    #[test]
+    fn pin_is_read_from_the_shared_flag() {
+        let (machine, _) = machine();
+        let flag = Arc::new(AtomicBool::new(true));
+        let mut machine = machine.with_pinned(flag.clone());
+        assert!(machine.is_pinned());
+        // Pinning an open capsule changes nothing
+        machine.force(true);
+        assert_eq!(machine.pin_changed(), None);
+        // The UI collapsing it is for the store to unpin
+        machine.force(false);
+        assert!(flag.load(Ordering::Relaxed));
+        assert_eq!(machine.phase, HoverPhase::Idle);
+    }
+
+    #[test]
+    fn modifier_arms_only_while_held() {
+        let (mut machine, clock) = alt_machine();
+        assert_eq!(inside(&mut machine), None);
+        assert_eq!(machine.phase, HoverPhase::Idle);
+        // Pressing it in the zone counts as the entry
+        clock.set_ms(50);
+        assert_eq!(with_alt(&mut machine, true), None);
+        clock.set_ms(150);
+        assert_eq!(with_alt(&mut machine, true), Some(HoverDecision::Expand));
+    }
+
+    #[test]
+    fn releasing_the_modifier_cancels_a_pending_expand() {
+        let (mut machine, clock) = alt_machine();
+        with_alt(&mut machine, true);
+        clock.set_ms(50);
+        assert_eq!(with_alt(&mut machine, false), None);
+        assert_eq!(machine.phase, HoverPhase::Idle);
+        clock.set_ms(500);
+        assert_eq!(machine.tick(), None);
+    }
+
+    #[test]
+    fn releasing_the_modifier_collapses() {
+        let (mut machine, clock) = alt_machine();
+        with_alt(&mut machine, true);
+        clock.set_ms(100);
+        assert_eq!(machine.tick(), Some(HoverDecision::Expand));
+
+        clock.set_ms(400);
+        assert_eq!(with_alt(&mut machine, false), None);
+        assert_eq!(
+            machine.phase,
+            HoverPhase::PendingCollapse {
+                deadline: clock.at_ms(600)
+            }
+        );
+        // Pressing it again in time keeps it open, like re-entering
+        clock.set_ms(500);
+        assert_eq!(with_alt(&mut machine, true), None);
+        assert_eq!(machine.phase, HoverPhase::Expanded);
+
+        clock.set_ms(700);
+        with_alt(&mut machine, false);
+        clock.set_ms(900);
+        assert_eq!(machine.tick(), Some(HoverDecision::Collapse));
+    }
+
+    #[test]
+    fn releasing_the_modifier_leaves_a_pinned_capsule_open() {
+        let (machine, clock) = alt_machine();
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut machine = machine.with_pinned(flag.clone());
+        with_alt(&mut machine, true);
+        pin(&mut machine, &flag, true);
+        with_alt(&mut machine, false);
+        clock.set_ms(1000);
+        assert_eq!(machine.tick(), None);
+        assert!(machine.is_expanded());
+    }
+}
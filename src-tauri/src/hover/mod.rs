@@ -0,0 +1,136 @@
+pub mod activation;
+pub mod geometry;
+pub mod intent;
+#[cfg(target_os = "linux")]
+pub mod linux;
+pub mod machine;
+pub mod monitor;
+pub mod schedule;
+#[cfg(target_os = "linux")]
+mod surface;
+
+pub use activation::{ActivationMode, Modifiers};
+pub use machine::{HoverDecision, HoverMachine};
+pub use monitor::{HoverMonitor, HoverStatus, HoverSwitch};
+pub use schedule::{PollScheduler, PollStats};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+/// Shares a [`HoverMachine`] between the pointer sources and a timer thread that
+/// resolves pending phases on time, however sparse the samples are.
+pub struct HoverDebounce {
+    machine: Mutex<HoverMachine>,
+    wake: Condvar,
+    stopped: AtomicBool,
+}
+
+impl HoverDebounce {
+    pub fn new(machine: HoverMachine) -> Self {
+        Self {
+            machine: Mutex::new(machine),
+            wake: Condvar::new(),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// Feeds a pointer sample and emits `notch-should-expand` /
+    /// `notch-should-collapse` if it settles a decision.
+    /// Position is in points.
+    pub fn feed(&self, app: &AppHandle, x: f64, y: f64, inside: bool, modifiers: Modifiers) {
+        self.run(app, |machine| machine.sample(x, y, inside, modifiers));
+    }
+
+    /// Feeds an enter/leave without a position (see [`HoverMachine::sample_crossing`]).
+    pub fn feed_crossing(&self, app: &AppHandle, inside: bool, modifiers: Modifiers) {
+        self.run(app, |machine| machine.sample_crossing(inside, modifiers));
+    }
+
+    /// Feeds a click on the capsule.
+    pub fn click(&self, app: &AppHandle) {
+        self.run(app, HoverMachine::click);
+    }
+
+    fn run(&self, app: &AppHandle, f: impl FnOnce(&mut HoverMachine) -> Option<HoverDecision>) {
+        let decision = match self.machine.lock() {
+            Ok(mut machine) => f(&mut machine),
+            Err(_) => return,
+        };
+        self.wake.notify_one();
+        announce(app, decision);
+    }
+
+    /// Mirrors an expand/collapse made by the UI.
+    pub fn force(&self, expanded: bool) {
+        if let Ok(mut machine) = self.machine.lock() {
+            machine.force(expanded);
+        }
+        self.wake.notify_one();
+    }
+
+    /// Catches up with a pin or unpin in the [`NotchStore`](crate::state::NotchStore),
+    /// expanding right away when pinning a collapsed capsule.
+    pub fn pin_changed(&self, app: &AppHandle) {
+        self.run(app, HoverMachine::pin_changed);
+    }
+
+    /// Drops pending decisions and forgets the pointer (see [`HoverMachine::reset`]).
+    pub fn reset(&self) {
+        if let Ok(mut machine) = self.machine.lock() {
+            machine.reset();
+        }
+        self.wake.notify_one();
+    }
+
+    /// Ends the timer thread.
+    pub fn stop(&self) {
+        // Set under the lock so the timer can't miss the wakeup between its
+        // check and its wait.
+        if let Ok(_machine) = self.machine.lock() {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+        self.wake.notify_all();
+    }
+
+    /// Starts the thread that fires decisions when their delay runs out, until
+    /// [`stop`](Self::stop).
+    pub fn spawn_timer(self: &Arc<Self>, app: &AppHandle) -> JoinHandle<()> {
+        let debounce = self.clone();
+        let app = app.clone();
+        thread::spawn(move || {
+            let Ok(mut machine) = debounce.machine.lock() else {
+                return;
+            };
+            while !debounce.stopped.load(Ordering::Relaxed) {
+                machine = match machine.deadline() {
+                    Some(deadline) => {
+                        let wait = deadline.saturating_duration_since(Instant::now());
+                        match debounce.wake.wait_timeout(machine, wait) {
+                            Ok((guard, _)) => guard,
+                            Err(_) => return,
+                        }
+                    }
+                    None => match debounce.wake.wait(machine) {
+                        Ok(guard) => guard,
+                        Err(_) => return,
+                    },
+                };
+                if debounce.stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let decision = machine.tick();
+                announce(&app, decision);
+            }
+        })
+    }
+}
+
+fn announce(app: &AppHandle, decision: Option<HoverDecision>) {
+    if let Some(decision) = decision {
+        eprintln!("Hover decision: {:?}", decision);
+        let _ = app.emit(decision.event(), ());
+    }
+}
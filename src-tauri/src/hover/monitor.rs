@@ -0,0 +1,250 @@
+use super::{HoverDebounce, PollScheduler, PollStats};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// How long shutdown waits for the threads. A poller can be stuck in a dispatch
+// to the main thread, which is the one shutting down; it's left to die with
+// the process rather than hang the exit.
+const JOIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Run state shared with the pointer sources: event handlers return early while
+/// paused, and poller threads sleep through [`sleep`](Self::sleep) so they wake
+/// up as soon as the monitor stops.
+#[derive(Default)]
+pub struct HoverSwitch {
+    paused: AtomicBool,
+    stopped: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl HoverSwitch {
+    /// True while paused or after shutdown; sources should drop their samples.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed) || self.is_stopped()
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.lock().map(|stopped| *stopped).unwrap_or(true)
+    }
+
+    /// Sleeps for `duration` unless the monitor stops first. Returns false once
+    /// stopped, which is the poller's cue to exit.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let Ok(stopped) = self.stopped.lock() else {
+            return false;
+        };
+        match self
+            .wake
+            .wait_timeout_while(stopped, duration, |stopped| !*stopped)
+        {
+            Ok((stopped, _)) => !*stopped,
+            Err(_) => false,
+        }
+    }
+
+    fn stop(&self) {
+        if let Ok(mut stopped) = self.stopped.lock() {
+            *stopped = true;
+        }
+        self.wake.notify_all();
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HoverStatus {
+    /// Started and not shut down.
+    pub running: bool,
+    pub paused: bool,
+    /// Which pointer source is in use, e.g. `"macos"`, `"x11"` or `"wayland"`.
+    pub backend: Option<&'static str>,
+    pub poll: Option<PollStats>,
+}
+
+/// Owns everything hover detection started: poller threads, the debounce timer
+/// and platform event monitors (through teardown callbacks). It can be paused,
+/// e.g. during a presentation, and shut down cleanly when the app exits.
+pub struct HoverMonitor {
+    switch: Arc<HoverSwitch>,
+    debounce: Arc<HoverDebounce>,
+    poll: Arc<Mutex<PollScheduler>>,
+    backend: Mutex<Option<&'static str>>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+    teardown: Mutex<Vec<Box<dyn FnOnce() + Send>>>,
+}
+
+impl HoverMonitor {
+    pub fn new(debounce: Arc<HoverDebounce>, poll: Arc<Mutex<PollScheduler>>) -> Self {
+        Self {
+            switch: Arc::new(HoverSwitch::default()),
+            debounce,
+            poll,
+            backend: Mutex::new(None),
+            threads: Mutex::new(Vec::new()),
+            teardown: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn switch(&self) -> Arc<HoverSwitch> {
+        self.switch.clone()
+    }
+
+    /// Records which pointer source started.
+    pub fn set_backend(&self, backend: &'static str) {
+        if let Ok(mut current) = self.backend.lock() {
+            *current = Some(backend);
+        }
+    }
+
+    /// Hands over a thread to be joined on shutdown. It must exit once
+    /// [`HoverSwitch::sleep`] returns false.
+    pub fn add_thread(&self, handle: JoinHandle<()>) {
+        if let Ok(mut threads) = self.threads.lock() {
+            threads.push(handle);
+        }
+    }
+
+    /// Registers cleanup for a resource the monitor owns, such as an event
+    /// monitor that has to be removed. Runs on the thread calling
+    /// [`shutdown`](Self::shutdown).
+    pub fn on_shutdown(&self, teardown: impl FnOnce() + Send + 'static) {
+        if let Ok(mut callbacks) = self.teardown.lock() {
+            callbacks.push(Box::new(teardown));
+        }
+    }
+
+    /// Stops acting on the pointer. Pending expand/collapse decisions are dropped
+    /// so nothing fires after the pause.
+    pub fn pause(&self) {
+        if !self.switch.paused.swap(true, Ordering::Relaxed) {
+            self.debounce.reset();
+            eprintln!("Hover detection paused");
+        }
+    }
+
+    pub fn resume(&self) {
+        if self.switch.paused.swap(false, Ordering::Relaxed) {
+            eprintln!("Hover detection resumed");
+        }
+    }
+
+    /// Whether a native pointer source started and hasn't been shut down.
+    pub fn is_running(&self) -> bool {
+        !self.switch.is_stopped() && self.backend.lock().is_ok_and(|backend| backend.is_some())
+    }
+
+    pub fn status(&self) -> HoverStatus {
+        HoverStatus {
+            running: self.is_running(),
+            paused: self.switch.paused.load(Ordering::Relaxed),
+            backend: self.backend.lock().ok().and_then(|backend| *backend),
+            poll: self
+                .poll
+                .lock()
+                .ok()
+                .map(|mut poll| poll.stats(Instant::now())),
+        }
+    }
+
+    /// Stops the threads, waits up to half a second for them and runs the
+    /// teardown callbacks. Safe to call more than once. On macOS this has to run
+    /// on the main thread, where event monitors are removed.
+    pub fn shutdown(&self) {
+        if self.switch.is_stopped() {
+            return;
+        }
+        self.switch.stop();
+        self.debounce.stop();
+        let mut threads = self
+            .threads
+            .lock()
+            .map(|mut threads| std::mem::take(&mut *threads))
+            .unwrap_or_default();
+        let deadline = Instant::now() + JOIN_TIMEOUT;
+        loop {
+            let (finished, busy): (Vec<_>, Vec<_>) =
+                threads.into_iter().partition(JoinHandle::is_finished);
+            for handle in finished {
+                let _ = handle.join();
+            }
+            threads = busy;
+            if threads.is_empty() || Instant::now() >= deadline {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        if !threads.is_empty() {
+            eprintln!(
+                "Hover: {} thread(s) still busy at shutdown, detaching",
+                threads.len()
+            );
+        }
+        let callbacks = self
+            .teardown
+            .lock()
+            .map(|mut callbacks| std::mem::take(&mut *callbacks))
+            .unwrap_or_default();
+        for teardown in callbacks {
+            teardown();
+        }
+        eprintln!("Hover detection stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotchConfig;
+    use crate::hover::HoverMachine;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::mpsc;
+
+    fn monitor() -> HoverMonitor {
+        let config = NotchConfig::default();
+        HoverMonitor::new(
+            Arc::new(HoverDebounce::new(HoverMachine::from_config(&config))),
+            Arc::new(Mutex::new(PollScheduler::from_config(&config.hover))),
+        )
+    }
+
+    #[test]
+    fn shutdown_wakes_and_joins_pollers() {
+        let monitor = monitor();
+        let switch = monitor.switch();
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        monitor.add_thread(thread::spawn(move || {
+            while switch.sleep(Duration::from_secs(60)) {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+
+        let started = Instant::now();
+        monitor.shutdown();
+        assert!(started.elapsed() < JOIN_TIMEOUT);
+        assert_eq!(polls.load(Ordering::Relaxed), 0);
+        assert!(monitor.switch().is_stopped());
+        assert!(monitor.threads.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn shutdown_detaches_a_stuck_thread() {
+        let monitor = monitor();
+        // Stands in for a main-thread dispatch that can't run during shutdown
+        let (release, blocked) = mpsc::channel::<()>();
+        monitor.add_thread(thread::spawn(move || {
+            let _ = blocked.recv();
+        }));
+        let torn_down = Arc::new(AtomicBool::new(false));
+        let flag = torn_down.clone();
+        monitor.on_shutdown(move || flag.store(true, Ordering::Relaxed));
+
+        let started = Instant::now();
+        monitor.shutdown();
+        assert!(started.elapsed() >= JOIN_TIMEOUT);
+        assert!(torn_down.load(Ordering::Relaxed));
+        let _ = release.send(());
+    }
+}
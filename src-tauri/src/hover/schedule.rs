@@ -0,0 +1,225 @@
+use crate::config::HoverConfig;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How often the poller still checks in while event monitors are delivering.
+pub const MONITOR_HEARTBEAT: Duration = Duration::from_secs(5);
+
+const STATS_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct PollStats {
+    /// Poller wakeups over the last minute.
+    pub wakeups_per_minute: usize,
+    /// Current wait between wakeups.
+    pub interval_ms: u64,
+    /// Whether event monitors are trusted and polling is down to the heartbeat.
+    pub monitors_active: bool,
+}
+
+/// Picks how long the hover poller sleeps between wakeups: `poll_interval_ms`
+/// near a zone, `idle_poll_interval_ms` far from all of them, and only a
+/// heartbeat while event monitors are known to deliver.
+///
+/// Monitors count as working after their first event, and stop counting when a
+/// poll finds the pointer moved without any event arriving since the last poll
+/// (e.g. accessibility permission was revoked). Methods that depend on time
+/// take the current time, so a test can drive them with a fake clock.
+pub struct PollScheduler {
+    near_interval: Duration,
+    far_interval: Duration,
+    near_distance: f64,
+    interval: Duration,
+    monitors_active: bool,
+    event_since_poll: bool,
+    last_position: Option<(f64, f64)>,
+    wakeups: VecDeque<Instant>,
+}
+
+impl PollScheduler {
+    pub fn from_config(config: &HoverConfig) -> Self {
+        Self::new(
+            Duration::from_millis(config.poll_interval_ms.value),
+            Duration::from_millis(config.idle_poll_interval_ms.value),
+            config.near_distance.value,
+        )
+    }
+
+    /// `near_distance` is in points.
+    pub fn new(near_interval: Duration, far_interval: Duration, near_distance: f64) -> Self {
+        Self {
+            near_interval,
+            // Never slower near a zone than away from it.
+            far_interval: far_interval.max(near_interval),
+            near_distance,
+            interval: near_interval,
+            monitors_active: false,
+            event_since_poll: false,
+            last_position: None,
+            wakeups: VecDeque::new(),
+        }
+    }
+
+    /// Wait before the next wakeup.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// An event monitor saw the pointer move.
+    pub fn note_monitor_event(&mut self) {
+        if !self.monitors_active {
+            eprintln!("Hover: event monitors are delivering, polling paused");
+        }
+        self.monitors_active = true;
+        self.event_since_poll = true;
+    }
+
+    /// Records a poller wakeup that found the pointer at `position`, `distance`
+    /// points away from the nearest zone, and returns the next interval.
+    pub fn record_poll(
+        &mut self,
+        now: Instant,
+        position: (f64, f64),
+        distance: Option<f64>,
+    ) -> Duration {
+        self.wakeups.push_back(now);
+        self.prune(now);
+
+        let moved = self.last_position.is_some_and(|last| last != position);
+        if self.monitors_active && moved && !self.event_since_poll {
+            eprintln!("Hover: pointer moved without monitor events, polling resumed");
+            self.monitors_active = false;
+        }
+        self.event_since_poll = false;
+        self.last_position = Some(position);
+
+        self.interval = if self.monitors_active {
+            MONITOR_HEARTBEAT
+        } else {
+            match distance {
+                Some(d) if d <= self.near_distance => self.near_interval,
+                _ => self.far_interval,
+            }
+        };
+        self.interval
+    }
+
+    pub fn stats(&mut self, now: Instant) -> PollStats {
+        self.prune(now);
+        PollStats {
+            wakeups_per_minute: self.wakeups.len(),
+            interval_ms: self.interval.as_millis() as u64,
+            monitors_active: self.monitors_active,
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while self
+            .wakeups
+            .front()
+            .is_some_and(|&t| now.saturating_duration_since(t) > STATS_WINDOW)
+        {
+            self.wakeups.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEAR: Duration = Duration::from_millis(16);
+    const FAR: Duration = Duration::from_millis(250);
+
+    // Near within 100 points of a zone
+    fn scheduler() -> PollScheduler {
+        PollScheduler::new(NEAR, FAR, 100.0)
+    }
+
+    fn at_ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn polls_faster_near_a_zone() {
+        let mut poll = scheduler();
+        let start = Instant::now();
+        assert_eq!(poll.interval(), NEAR);
+        assert_eq!(poll.record_poll(start, (0.0, 0.0), Some(50.0)), NEAR);
+        assert_eq!(poll.record_poll(start, (0.0, 0.0), Some(100.0)), NEAR);
+        assert_eq!(poll.record_poll(start, (0.0, 0.0), Some(101.0)), FAR);
+        // No screens at all
+        assert_eq!(poll.record_poll(start, (0.0, 0.0), None), FAR);
+        assert_eq!(poll.interval(), FAR);
+    }
+
+    #[test]
+    fn far_interval_is_never_below_near() {
+        let mut poll = PollScheduler::new(FAR, NEAR, 100.0);
+        assert_eq!(poll.record_poll(Instant::now(), (0.0, 0.0), None), FAR);
+    }
+
+    #[test]
+    fn delivering_monitors_drop_polling_to_the_heartbeat() {
+        let mut poll = scheduler();
+        let start = Instant::now();
+        poll.note_monitor_event();
+        assert_eq!(
+            poll.record_poll(start, (10.0, 10.0), Some(0.0)),
+            MONITOR_HEARTBEAT
+        );
+
+        // Still at rest, or moved with an event in between
+        assert_eq!(
+            poll.record_poll(at_ms(start, 5000), (10.0, 10.0), Some(0.0)),
+            MONITOR_HEARTBEAT
+        );
+        poll.note_monitor_event();
+        assert_eq!(
+            poll.record_poll(at_ms(start, 10_000), (20.0, 10.0), Some(0.0)),
+            MONITOR_HEARTBEAT
+        );
+        assert!(poll.stats(at_ms(start, 10_000)).monitors_active);
+    }
+
+    #[test]
+    fn moving_without_events_resumes_polling() {
+        let mut poll = scheduler();
+        let start = Instant::now();
+        poll.note_monitor_event();
+        poll.record_poll(start, (10.0, 10.0), Some(0.0));
+
+        // The pointer moved, but the monitors said nothing
+        assert_eq!(
+            poll.record_poll(at_ms(start, 5000), (30.0, 10.0), Some(0.0)),
+            NEAR
+        );
+        assert!(!poll.stats(at_ms(start, 5000)).monitors_active);
+
+        // Until they deliver again
+        poll.note_monitor_event();
+        assert_eq!(
+            poll.record_poll(at_ms(start, 5016), (40.0, 10.0), Some(0.0)),
+            MONITOR_HEARTBEAT
+        );
+    }
+
+    #[test]
+    fn stats_count_wakeups_over_the_last_minute() {
+        let mut poll = scheduler();
+        let start = Instant::now();
+        for second in 0..90 {
+            poll.record_poll(at_ms(start, second * 1000), (0.0, 0.0), Some(500.0));
+        }
+
+        let stats = poll.stats(at_ms(start, 89_000));
+        // 29 s to 89 s, both ends included
+        assert_eq!(stats.wakeups_per_minute, 61);
+        assert_eq!(stats.interval_ms, 250);
+        assert!(!stats.monitors_active);
+
+        // Nothing since
+        assert_eq!(poll.stats(at_ms(start, 150_000)).wakeups_per_minute, 0);
+    }
+}
@@ -0,0 +1,72 @@
+use super::{HoverDebounce, HoverSwitch, Modifiers};
+use gtk::gdk::{EventCrossing, EventMask, ModifierType, NotifyType};
+use gtk::glib::Propagation;
+use gtk::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, WebviewWindow};
+
+/// Whether the window is on a Wayland display, where clients can't read the
+/// global pointer position. Main thread only.
+pub fn is_wayland(window: &WebviewWindow) -> bool {
+    window
+        .gtk_window()
+        .map(|w| WidgetExt::display(&w).type_().name() == "GdkWaylandDisplay")
+        .unwrap_or(false)
+}
+
+/// Tracks the pointer through enter/leave on the capsule's own surface. The
+/// window is the hover zone here, so zone shapes and the exit margin don't
+/// apply, and with no motion outside the window every entry counts as intent.
+/// The handlers go away with the window, so there's nothing to tear down.
+/// Main thread only.
+pub fn watch(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    debounce: Arc<HoverDebounce>,
+    switch: Arc<HoverSwitch>,
+) -> Result<(), String> {
+    let gtk_window = window.gtk_window().map_err(|e| e.to_string())?;
+    gtk_window.add_events(EventMask::ENTER_NOTIFY_MASK | EventMask::LEAVE_NOTIFY_MASK);
+    let inside = Cell::new(false);
+
+    let report = move |app: &AppHandle, now_inside: bool, event: &EventCrossing| {
+        if switch.is_paused() {
+            inside.set(false);
+            return;
+        }
+        if inside.replace(now_inside) != now_inside {
+            eprintln!("Mouse hover state changed: inside={}", now_inside);
+            let _ = app.emit("notch-hover", serde_json::json!({ "inside": now_inside }));
+        }
+        // GDK event coordinates are already in logical points.
+        let (x, y) = event.position();
+        let state = event.state();
+        let modifiers = Modifiers {
+            shift: state.contains(ModifierType::SHIFT_MASK),
+            control: state.contains(ModifierType::CONTROL_MASK),
+            alt: state.contains(ModifierType::MOD1_MASK),
+            meta: state.contains(ModifierType::SUPER_MASK),
+        };
+        debounce.feed(app, x, y, now_inside, modifiers);
+    };
+    let report = Rc::new(report);
+
+    let app_enter = app.clone();
+    let report_enter = report.clone();
+    gtk_window.connect_enter_notify_event(move |_, event| {
+        report_enter(&app_enter, true, event);
+        Propagation::Proceed
+    });
+
+    let app_leave = app.clone();
+    gtk_window.connect_leave_notify_event(move |_, event| {
+        // Moving onto the webview inside the window isn't leaving it.
+        if event.detail() != NotifyType::Inferior {
+            report(&app_leave, false, event);
+        }
+        Propagation::Proceed
+    });
+    Ok(())
+}
@@ -3,111 +3,61 @@
 #[allow(unused_imports)]
 mod macos;
 
+mod animation;
+mod audio;
 mod config;
+mod controller;
+mod display;
+// Only macOS and Linux have pointer backends feeding the hover debounce
+#[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+mod hover;
+mod media;
+mod peek;
+mod pin;
+mod silhouette;
+mod state;
+
+use peek::PeekTimer;
+use state::{NotchDisplay, NotchPhase, NotchStage, NotchStore, NotchView};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use tauri::{Manager, State};
 
-#[cfg(all(desktop, target_os = "macos"))]
-use tauri::Manager;
-
-use block2::{Block, RcBlock};
-use objc2_app_kit::{NSEvent, NSEventMask, NSScreen};
-use objc2_foundation::MainThreadMarker; // for NSScreen::screens(mtm)
-use objc2_foundation::NSPoint;
 #[cfg(target_os = "macos")]
-use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
+use block2::RcBlock;
 #[cfg(target_os = "macos")]
-use std::{
-    ptr::NonNull,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
-    },
-    thread,
-    time::{Duration, Instant},
-};
-use tauri::Emitter; // needed for AppHandle.emit(...)
+use objc2_app_kit::{NSEvent, NSEventMask, NSEventModifierFlags, NSScreen};
 #[cfg(target_os = "macos")]
-use tauri::State;
-
-// Media info structure
-#[derive(serde::Serialize, Clone, Debug)]
-struct MediaInfo {
-    title: String,
-    artist: String,
-    album: String,
-    artwork_url: Option<String>,
-    duration: f64,
-    elapsed: f64,
-    is_playing: bool,
-}
-
-// ---------- Notch metrics (accurate; macOS 12+) ----------
+use objc2_foundation::MainThreadMarker; // for NSScreen::screens(mtm)
 #[cfg(target_os = "macos")]
-#[derive(serde::Serialize)]
-struct NotchDimensions {
-    width_pts: f64,
-    top_inset_pts: f64,
-    width_px: f64,
-    top_inset_px: f64,
-    scale: f64,
-}
-
+use objc2_foundation::NSPoint;
 #[cfg(target_os = "macos")]
-struct HoverState {
-    expanded: Arc<AtomicBool>,
-}
-
+use std::{ptr::NonNull, thread, time::Instant};
 #[cfg(target_os = "macos")]
-impl Default for HoverState {
-    fn default() -> Self {
+use tauri::Emitter;
+
+struct HoverState {
+    debounce: Arc<hover::HoverDebounce>,
+    poll: Arc<Mutex<hover::PollScheduler>>,
+    monitor: hover::HoverMonitor,
+}
+
+impl HoverState {
+    // `pinned` is the store's flag; hover only reads it
+    fn new(pinned: Arc<AtomicBool>) -> Self {
+        let notch_config = config::NotchConfig::get();
+        let debounce = Arc::new(hover::HoverDebounce::new(
+            hover::HoverMachine::from_config(notch_config).with_pinned(pinned),
+        ));
+        let poll = Arc::new(Mutex::new(hover::PollScheduler::from_config(&notch_config.hover)));
         Self {
-            expanded: Arc::new(AtomicBool::new(false)),
+            monitor: hover::HoverMonitor::new(debounce.clone(), poll.clone()),
+            debounce,
+            poll,
         }
     }
 }
 
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn get_notch_dimensions(window: tauri::Window) -> Option<NotchDimensions> {
-    use core::ffi::c_void;
-    use objc2::runtime::AnyObject;
-    use objc2_app_kit::NSWindow;
-
-    // NSWindow pointer
-    let raw: *mut c_void = window.ns_window().ok()?;
-    if raw.is_null() {
-        return None;
-    }
-    let obj: *mut AnyObject = raw.cast();
-    let ns_win: &NSWindow = unsafe { &*(obj as *mut NSWindow) };
-
-    // Screen
-    let screen = ns_win.screen().or_else(|| {
-        let mtm = MainThreadMarker::new()?;
-        NSScreen::mainScreen(mtm)
-    })?;
-
-    // Notch presence
-    let insets = screen.safeAreaInsets();
-    if insets.top <= 0.0 {
-        return None;
-    }
-
-    // True notch width
-    let frame = screen.frame();
-    let left = screen.auxiliaryTopLeftArea();
-    let right = screen.auxiliaryTopRightArea();
-    let width_pts = (frame.size.width - left.size.width - right.size.width).max(0.0);
-
-    let scale = screen.backingScaleFactor() as f64;
-    Some(NotchDimensions {
-        width_pts,
-        top_inset_pts: insets.top,
-        width_px: width_pts * scale,
-        top_inset_px: insets.top * scale,
-        scale,
-    })
-}
-
 #[cfg(target_os = "macos")]
 fn elevate_to_status_bar(win: &tauri::WebviewWindow) -> tauri::Result<()> {
     use core::ffi::c_void;
@@ -190,15 +140,160 @@ fn ensure_accessibility(prompt: bool) -> bool {
     }
 }
 
-#[cfg(target_os = "macos")]
+// Everything the UI needs to know about the notch, in one place
 #[tauri::command]
-fn set_notch_expanded(expanded: bool, state: State<HoverState>) {
-    state.expanded.store(expanded, Ordering::Relaxed);
+fn get_notch_state(store: State<NotchStore>) -> state::NotchState {
+    store.get()
 }
 
-#[cfg(not(target_os = "macos"))]
+// The UI settled the notch open or closed
+#[tauri::command]
+fn set_notch_expanded(expanded: bool, app: tauri::AppHandle) {
+    set_phase(&app, NotchPhase::settled(expanded));
+}
+
+// The UI started (or finished) an expand/collapse animation
+#[tauri::command]
+fn set_notch_phase(phase: NotchPhase, app: tauri::AppHandle) {
+    set_phase(&app, phase);
+}
+
+fn set_phase(app: &tauri::AppHandle, phase: NotchPhase) {
+    let mut unpinned = false;
+    app.state::<NotchStore>().update(app, |state| {
+        let was_pinned = state.pinned;
+        state.set_phase(phase);
+        unpinned = was_pinned && !state.pinned;
+    });
+    // Hover and the spectrum only care where the notch ends up
+    if phase.is_settled() {
+        let expanded = phase == NotchPhase::Expanded;
+        app.state::<HoverState>().debounce.force(expanded);
+        // Settling collapsed unpins (see `NotchState::set_phase`)
+        if unpinned {
+            pin_changed(app, false);
+        }
+        app.state::<audio::SpectrumState>()
+            .set_expanded(app, expanded);
+    }
+}
+
+// Pinning keeps the capsule expanded; hover never collapses it
 #[tauri::command]
-fn set_notch_expanded(_expanded: bool) {}
+fn set_notch_pinned(pinned: bool, app: tauri::AppHandle) {
+    set_pinned(&app, pinned);
+}
+
+#[tauri::command]
+fn get_notch_pinned(store: State<NotchStore>) -> bool {
+    store.get().pinned
+}
+
+// The store owns the pinned flag; hover reads it and catches up here
+fn set_pinned(app: &tauri::AppHandle, pinned: bool) {
+    if app
+        .state::<NotchStore>()
+        .update(app, |state| state.pinned = pinned)
+    {
+        pin_changed(app, pinned);
+    }
+}
+
+// Persists a pin change already in the store and lets hover catch up
+fn pin_changed(app: &tauri::AppHandle, pinned: bool) {
+    pin::save(app, pinned);
+    app.state::<HoverState>().debounce.pin_changed(app);
+}
+
+// The tab shown while expanded; tabs can have their own expanded size
+#[tauri::command]
+fn set_notch_view(
+    view: NotchView,
+    store: State<NotchStore>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    if !store.update(&app, |state| state.active_view = view) {
+        return Ok(());
+    }
+    let (width, height) = config::NotchConfig::get().dimensions.expanded_size(view);
+    notch_controller(&app)?.resize_expanded(width, height)
+}
+
+// Tracks focus and the hosting display of the capsule window
+fn capsule_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    if window.label() != "notch-capsule" {
+        return;
+    }
+    let app = window.app_handle();
+    match event {
+        tauri::WindowEvent::Focused(focused) => {
+            let focused = *focused;
+            app.state::<NotchStore>()
+                .update(app, |state| state.focused = focused);
+        }
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::ScaleFactorChanged { .. } => {
+            refresh_display(app, window.current_monitor().ok().flatten());
+        }
+        _ => {}
+    }
+}
+
+fn refresh_display(app: &tauri::AppHandle, monitor: Option<tauri::Monitor>) {
+    let display = monitor.as_ref().map(NotchDisplay::from);
+    app.state::<NotchStore>()
+        .update(app, |state| state.display = display);
+}
+
+// Turns hover detection off and on, e.g. for presentations
+#[tauri::command]
+fn pause_hover(state: State<HoverState>) {
+    state.monitor.pause();
+}
+
+#[tauri::command]
+fn resume_hover(state: State<HoverState>) {
+    state.monitor.resume();
+}
+
+#[tauri::command]
+fn hover_status(state: State<HoverState>) -> hover::HoverStatus {
+    state.monitor.status()
+}
+
+// Clicks on the collapsed capsule; they expand it in click and hybrid activation modes
+#[tauri::command]
+fn notch_click(state: State<HoverState>, app: tauri::AppHandle) {
+    if !state.monitor.switch().is_paused() {
+        state.debounce.click(&app);
+    }
+}
+
+// Pointer crossings seen by the webview. Only used where no native backend
+// tracks the pointer, so the two don't contradict each other
+#[tauri::command]
+fn notch_pointer(
+    inside: bool,
+    modifiers: Option<hover::Modifiers>,
+    state: State<HoverState>,
+    app: tauri::AppHandle,
+) {
+    if state.monitor.is_running() || state.monitor.switch().is_paused() {
+        return;
+    }
+    state
+        .debounce
+        .feed_crossing(&app, inside, modifiers.unwrap_or_default());
+}
+
+// Poller wakeups, for checking that hover polling backs off
+#[tauri::command]
+fn hover_poll_stats(state: State<HoverState>) -> Option<hover::PollStats> {
+    state
+        .poll
+        .lock()
+        .ok()
+        .map(|mut poll| poll.stats(std::time::Instant::now()))
+}
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
@@ -257,7 +352,11 @@ fn set_capsule_focus(focus: bool, app: tauri::AppHandle) -> Result<(), String> {
         .recv()
         .map_err(|_| "focus change channel dropped".to_string())?
     {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+            app.state::<NotchStore>()
+                .update(&app, |state| state.focused = focus);
+            Ok(())
+        }
         Err(e) => Err(e),
     }
 }
@@ -268,498 +367,211 @@ fn set_capsule_focus(_focus: bool, _app: tauri::AppHandle) -> Result<(), String>
     Ok(())
 }
 
-// Get currently playing media from macOS Now Playing (works with ALL apps)
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn get_current_media() -> Option<MediaInfo> {
-    use std::process::Command;
-
-    // Use AppleScript to get Now Playing info from macOS system-wide
-    // This works with Music, Spotify, Chrome, Safari, VLC, and ANY app that reports to Now Playing
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(r#"
-            use framework "Foundation"
-            use framework "MediaPlayer"
-            use scripting additions
-            
-            try
-                -- Get Now Playing info from system
-                set infoCenter to current application's MPNowPlayingInfoCenter's defaultCenter()
-                set nowPlayingInfo to infoCenter's nowPlayingInfo()
-                
-                if nowPlayingInfo is not missing value then
-                    set titleKey to current application's MPMediaItemPropertyTitle
-                    set artistKey to current application's MPMediaItemPropertyArtist
-                    set albumKey to current application's MPMediaItemPropertyAlbumTitle
-                    set durationKey to current application's MPMediaItemPropertyPlaybackDuration
-                    set elapsedKey to current application's MPNowPlayingInfoPropertyElapsedPlaybackTime
-                    set rateKey to current application's MPNowPlayingInfoPropertyPlaybackRate
-                    
-                    set trackTitle to (nowPlayingInfo's objectForKey:titleKey) as text
-                    set trackArtist to (nowPlayingInfo's objectForKey:artistKey) as text
-                    set trackAlbum to (nowPlayingInfo's objectForKey:albumKey) as text
-                    set trackDuration to (nowPlayingInfo's objectForKey:durationKey) as real
-                    set trackElapsed to (nowPlayingInfo's objectForKey:elapsedKey) as real
-                    set playbackRate to (nowPlayingInfo's objectForKey:rateKey) as real
-                    
-                    set isPlaying to (playbackRate > 0)
-                    
-                    return trackTitle & "|||" & trackArtist & "|||" & trackAlbum & "|||" & trackDuration & "|||" & trackElapsed & "|||" & isPlaying & "|||" & (current date)
-                end if
-            end try
-            
-            -- Fallback: Try common music apps if Now Playing API fails
-            try
-                tell application "System Events"
-                    set musicRunning to (name of processes) contains "Music"
-                end tell
-                
-                if musicRunning then
-                    tell application "Music"
-                        if player state is not stopped then
-                            set trackName to name of current track
-                            set artistName to artist of current track
-                            set albumName to album of current track
-                            set trackDuration to duration of current track
-                            set trackPosition to player position
-                            set isPlaying to (player state is playing)
-                            return trackName & "|||" & artistName & "|||" & albumName & "|||" & trackDuration & "|||" & trackPosition & "|||" & isPlaying & "|||" & (current date)
-                        end if
-                    end tell
-                end if
-            end try
-            
-            -- Try Spotify
-            try
-                tell application "System Events"
-                    set spotifyRunning to (name of processes) contains "Spotify"
-                end tell
-                
-                if spotifyRunning then
-                    tell application "Spotify"
-                        if player state is not stopped then
-                            set trackName to name of current track
-                            set artistName to artist of current track
-                            set albumName to album of current track
-                            set trackDuration to duration of current track / 1000
-                            set trackPosition to player position
-                            set isPlaying to (player state is playing)
-                            return trackName & "|||" & artistName & "|||" & albumName & "|||" & trackDuration & "|||" & trackPosition & "|||" & isPlaying & "|||" & (current date)
-                        end if
-                    end tell
-                end if
-            end try
-            
-            return ""
-        "#)
-        .output()
-        .ok()?;
-
-    if output.status.success() {
-        let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if !result.is_empty() {
-            let parts: Vec<&str> = result.split("|||").collect();
-            if parts.len() >= 6 {
-                return Some(MediaInfo {
-                    title: parts[0].to_string(),
-                    artist: parts[1].to_string(),
-                    album: parts[2].to_string(),
-                    artwork_url: None, // Will fetch separately
-                    duration: parts[3].parse().unwrap_or(0.0),
-                    elapsed: parts[4].parse().unwrap_or(0.0),
-                    is_playing: parts[5] == "true",
-                });
-            }
-        }
-    }
-
-    None
-}
-
-// Get album artwork as base64 data URL
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn get_media_artwork() -> Option<String> {
-    use std::process::Command;
-
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(r#"
-            try
-                tell application "System Events"
-                    set musicRunning to (name of processes) contains "Music"
-                end tell
-                
-                if musicRunning then
-                    tell application "Music"
-                        if player state is not stopped then
-                            set artworkData to data of artwork 1 of current track
-                            return artworkData as «class PNGf»
-                        end if
-                    end tell
-                end if
-            end try
-            
-            try
-                tell application "System Events"
-                    set spotifyRunning to (name of processes) contains "Spotify"
-                end tell
-                
-                if spotifyRunning then
-                    tell application "Spotify"
-                        if player state is not stopped then
-                            return artwork url of current track
-                        end if
-                    end tell
-                end if
-            end try
-            
-            return ""
-        "#)
-        .output()
-        .ok()?;
-
-    if output.status.success() {
-        let stdout = output.stdout;
-        if stdout.is_empty() {
-            return None;
-        }
-
-        match std::str::from_utf8(&stdout) {
-            Ok(text) => {
-                let trimmed = text.trim();
-                if trimmed.starts_with("http") && !trimmed.is_empty() && trimmed != "missing value" {
-                    return Some(trimmed.to_string());
-                }
-                return None;
-            }
-            Err(_) => {
-                let data = if stdout.last() == Some(&b'\n') {
-                    &stdout[..stdout.len().saturating_sub(1)]
-                } else {
-                    &stdout[..]
-                };
-                if !data.is_empty() {
-                    let encoded = BASE64_STANDARD.encode(data);
-                    if !encoded.is_empty() {
-                        return Some(format!("data:image/png;base64,{}", encoded));
-                    }
-                }
-            }
-        }
-    }
-
-    None
-}
-
-#[cfg(not(target_os = "macos"))]
-#[tauri::command]
-fn get_current_media() -> Option<MediaInfo> {
-    None
-}
-
-#[cfg(not(target_os = "macos"))]
-#[tauri::command]
-fn get_media_artwork() -> Option<String> {
-    None
-}
-
-// Control media playback
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn media_play_pause() -> bool {
-    use std::process::Command;
-
-    // Try Music app
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(r#"
-            tell application "System Events"
-                set musicRunning to (name of processes) contains "Music"
-            end tell
-            
-            if musicRunning then
-                tell application "Music"
-                    playpause
-                    return true
-                end tell
-            end if
-            
-            -- Try Spotify
-            tell application "System Events"
-                set spotifyRunning to (name of processes) contains "Spotify"
-            end tell
-            
-            if spotifyRunning then
-                tell application "Spotify"
-                    playpause
-                    return true
-                end tell
-            end if
-            
-            return false
-        "#)
-        .output()
-        .ok();
-
-    output.map(|o| o.status.success()).unwrap_or(false)
-}
-
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn media_next_track() -> bool {
-    use std::process::Command;
-
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(r#"
-            tell application "System Events"
-                set musicRunning to (name of processes) contains "Music"
-            end tell
-            
-            if musicRunning then
-                tell application "Music"
-                    next track
-                    return true
-                end tell
-            end if
-            
-            tell application "System Events"
-                set spotifyRunning to (name of processes) contains "Spotify"
-            end tell
-            
-            if spotifyRunning then
-                tell application "Spotify"
-                    next track
-                    return true
-                end tell
-            end if
-            
-            return false
-        "#)
-        .output()
-        .ok();
-
-    output.map(|o| o.status.success()).unwrap_or(false)
-}
-
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn media_previous_track() -> bool {
-    use std::process::Command;
-
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(r#"
-            tell application "System Events"
-                set musicRunning to (name of processes) contains "Music"
-            end tell
-            
-            if musicRunning then
-                tell application "Music"
-                    previous track
-                    return true
-                end tell
-            end if
-            
-            tell application "System Events"
-                set spotifyRunning to (name of processes) contains "Spotify"
-            end tell
-            
-            if spotifyRunning then
-                tell application "Spotify"
-                    previous track
-                    return true
-                end tell
-            end if
-            
-            return false
-        "#)
-        .output()
-        .ok();
-
-    output.map(|o| o.status.success()).unwrap_or(false)
-}
-
-#[cfg(not(target_os = "macos"))]
-#[tauri::command]
-fn media_play_pause() -> bool {
-    false
-}
-
-#[cfg(not(target_os = "macos"))]
-#[tauri::command]
-fn media_next_track() -> bool {
-    false
-}
-
-#[cfg(not(target_os = "macos"))]
-#[tauri::command]
-fn media_previous_track() -> bool {
-    false
-}
-
-// Seek to a specific position in the track
-#[cfg(target_os = "macos")]
-#[tauri::command]
-fn media_seek(position: f64) -> bool {
-    use std::process::Command;
-
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(format!(
-            r#"
-            tell application "System Events"
-                set musicRunning to (name of processes) contains "Music"
-            end tell
-            
-            if musicRunning then
-                tell application "Music"
-                    set player position to {}
-                    return true
-                end tell
-            end if
-            
-            tell application "System Events"
-                set spotifyRunning to (name of processes) contains "Spotify"
-            end tell
-            
-            if spotifyRunning then
-                tell application "Spotify"
-                    set player position to {}
-                    return true
-                end tell
-            end if
-            
-            return false
-            "#,
-            position, position
-        ))
-        .output()
-        .ok();
-
-    output.map(|o| o.status.success()).unwrap_or(false)
-}
-
-#[cfg(not(target_os = "macos"))]
-#[tauri::command]
-fn media_seek(_position: f64) -> bool {
-    false
-}
-
 #[cfg(target_os = "macos")]
 fn handle_mouse_move<F>(
     st: &Arc<Mutex<(bool, Instant)>>,
+    debounce: &hover::HoverDebounce,
     app_handle: &tauri::AppHandle,
     hover_zone: &F,
-) where
-    F: Fn(NSPoint) -> Option<(f64, f64, f64, f64)>,
+) -> NSPoint
+where
+    F: Fn(NSPoint, bool) -> Option<hover::geometry::HoverZone>,
 {
     let p: NSPoint = NSEvent::mouseLocation();
-    let inside = if let Some((x, y, w, h)) = hover_zone(p) {
-        p.x >= x && p.x <= x + w && p.y >= y && p.y <= y + h
-    } else {
-        false
-    };
 
     let mut lock = st.lock().unwrap();
     let (was_inside, _ts) = *lock;
     let now = Instant::now();
+    // Once inside, the pointer is held to the larger exit zone (hysteresis)
+    let zone = hover_zone(p, was_inside);
+    let inside = zone.is_some();
 
-    // Emit immediately on state change; debouncing is handled in Svelte
+    // Emit raw transitions immediately; the debounced decisions come from `debounce`
     if inside != was_inside {
         *lock = (inside, now);
-        eprintln!("Mouse hover state changed: inside={}", inside);
+        eprintln!(
+            "Mouse hover state changed: inside={} screen={:?}",
+            inside,
+            zone.map(|z| z.screen)
+        );
         let _ = app_handle.emit("notch-hover", serde_json::json!({ "inside": inside }));
     }
+    drop(lock);
+    debounce.feed(app_handle, p.x, p.y, inside, current_modifiers());
+    p
 }
 
 #[cfg(target_os = "macos")]
-fn start_hover_monitors(app: &tauri::AppHandle, expanded_flag: Arc<AtomicBool>) {
-    // --- shared debounce state ---
-    let st = Arc::new(Mutex::new((false, Instant::now())));
+fn current_modifiers() -> hover::Modifiers {
+    let flags = NSEvent::modifierFlags_class();
+    hover::Modifiers {
+        shift: flags.contains(NSEventModifierFlags::Shift),
+        control: flags.contains(NSEventModifierFlags::Control),
+        alt: flags.contains(NSEventModifierFlags::Option),
+        meta: flags.contains(NSEventModifierFlags::Command),
+    }
+}
 
-    // Helper: top-center hover zone on the screen where the mouse is
-    let expanded_flag_for_zone = expanded_flag.clone();
-    let hover_zone = Arc::new(move |mouse: NSPoint| -> Option<(f64, f64, f64, f64)> {
-        let mtm = MainThreadMarker::new()?;
-        let screens = NSScreen::screens(mtm);
-        let mut frame = None;
-        for i in 0..screens.len() {
-            let s = screens.objectAtIndex(i as _);
-            let f = s.frame();
-            let inside = mouse.x >= f.origin.x
-                && mouse.x <= f.origin.x + f.size.width
-                && mouse.y >= f.origin.y
-                && mouse.y <= f.origin.y + f.size.height;
-            if inside {
-                frame = Some(f);
-                break;
+// All screens in Cocoa's global, y-up point space
+#[cfg(target_os = "macos")]
+fn screen_layout() -> Option<hover::geometry::ScreenLayout> {
+    use hover::geometry::{Rect, ScreenFrame, ScreenLayout, Units, YAxis};
+
+    let mtm = MainThreadMarker::new()?;
+    let screens = NSScreen::screens(mtm);
+    let screens = (0..screens.len())
+        .map(|i| {
+            let screen = screens.objectAtIndex(i as _);
+            let f = screen.frame();
+            ScreenFrame {
+                frame: Rect::new(f.origin.x, f.origin.y, f.size.width, f.size.height),
+                scale_factor: screen.backingScaleFactor() as f64,
             }
-        }
-        let f = frame?;
-        // tune these to your capsule size
-        let expanded = expanded_flag_for_zone.load(Ordering::Relaxed);
-        let cfg = config::NotchConfig::get();
-        let (zone_w, zone_h) = if expanded {
-            (cfg.hover.expanded_zone_width.value, cfg.hover.expanded_zone_height.value)
-        } else {
-            (cfg.hover.collapsed_zone_width.value, cfg.hover.collapsed_zone_height.value)
-        };
-        let x = f.origin.x + (f.size.width - zone_w) * 0.5;
-        let y = f.origin.y + f.size.height - zone_h;
-        Some((x, y, zone_w, zone_h))
-    });
+        })
+        .collect();
+    Some(ScreenLayout {
+        screens,
+        y_axis: YAxis::Up,
+        units: Units::Points,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn start_hover_monitors(
+    app: &tauri::AppHandle,
+    zones: Arc<state::SharedStage>,
+    debounce: Arc<hover::HoverDebounce>,
+    poll: Arc<Mutex<hover::PollScheduler>>,
+    monitor: &hover::HoverMonitor,
+) {
+    // --- shared debounce state ---
+    let st = Arc::new(Mutex::new((false, Instant::now())));
+    monitor.add_thread(debounce.spawn_timer(app));
+    let switch = monitor.switch();
+
+    // Helper: the hover zone under the mouse, on whichever screen it's on
+    let zones_for_zone = zones.clone();
+    let hover_zone = Arc::new(
+        move |mouse: NSPoint, inside: bool| -> Option<hover::geometry::HoverZone> {
+            let layout = screen_layout()?;
+            let stage = zones_for_zone.load();
+            layout.zone_at(config::NotchConfig::get(), stage, inside, mouse.x, mouse.y)
+        },
+    );
+    let zone_distance = move |mouse: NSPoint| -> Option<f64> {
+        let stage = zones.load();
+        screen_layout()?.distance_to_zone(config::NotchConfig::get(), stage, mouse.x, mouse.y)
+    };
 
     // --- Global monitor: events targeted at other apps (works when you are NOT key) ---
     let st_global = st.clone();
     let app_handle_global = app.clone();
     let hover_zone_global = hover_zone.clone();
+    let debounce_global = debounce.clone();
+    let poll_global = poll.clone();
+    let switch_global = switch.clone();
     let global_closure = move |_: NonNull<NSEvent>| {
-        handle_mouse_move(&st_global, &app_handle_global, &*hover_zone_global);
+        if switch_global.is_paused() {
+            return;
+        }
+        handle_mouse_move(&st_global, &debounce_global, &app_handle_global, &*hover_zone_global);
+        if let Ok(mut poll) = poll_global.lock() {
+            poll.note_monitor_event();
+        }
     };
 
-    let global_handler: &'static Block<dyn Fn(NonNull<NSEvent>)> =
-        Box::leak(Box::new(RcBlock::new(global_closure)));
-    let mouse_moved = NSEventMask::from_bits_truncate(1 << 6); // mouseMoved
-    if let Some(monitor) = unsafe {
-        NSEvent::addGlobalMonitorForEventsMatchingMask_handler(mouse_moved, global_handler)
+    let global_handler: RcBlock<dyn Fn(NonNull<NSEvent>)> = RcBlock::new(global_closure);
+    // mouseMoved, plus modifier changes so hover_with_modifier reacts without movement
+    let event_mask = NSEventMask::from_bits_truncate(1 << 6) | NSEventMask::FlagsChanged;
+    if let Some(token) = unsafe {
+        NSEvent::addGlobalMonitorForEventsMatchingMask_handler(event_mask, &global_handler)
     } {
-        std::mem::forget(monitor);
+        // The handler block stays alive until the monitor is removed
+        let owned = MainThreadOnly((token, global_handler));
+        monitor.on_shutdown(move || {
+            let (token, _handler) = owned.into_inner();
+            unsafe { NSEvent::removeMonitor(&token) };
+        });
     }
 
     // --- Local monitor: events targeted at YOUR app (fires when you ARE key) ---
     let st_local = st.clone();
     let app_handle_local = app.clone();
     let hover_zone_local = hover_zone.clone();
+    let debounce_local = debounce.clone();
+    let poll_local = poll.clone();
+    let switch_local = switch.clone();
     let local_closure = move |evt: NonNull<NSEvent>| -> *mut NSEvent {
-        handle_mouse_move(&st_local, &app_handle_local, &*hover_zone_local);
+        if switch_local.is_paused() {
+            return evt.as_ptr();
+        }
+        handle_mouse_move(&st_local, &debounce_local, &app_handle_local, &*hover_zone_local);
+        if let Ok(mut poll) = poll_local.lock() {
+            poll.note_monitor_event();
+        }
         evt.as_ptr()
     };
-    let local_handler: &'static Block<dyn Fn(NonNull<NSEvent>) -> *mut NSEvent> =
-        Box::leak(Box::new(RcBlock::new(local_closure)));
-    if let Some(monitor) = unsafe {
-        NSEvent::addLocalMonitorForEventsMatchingMask_handler(mouse_moved, local_handler)
+    let local_handler: RcBlock<dyn Fn(NonNull<NSEvent>) -> *mut NSEvent> =
+        RcBlock::new(local_closure);
+    if let Some(token) = unsafe {
+        NSEvent::addLocalMonitorForEventsMatchingMask_handler(event_mask, &local_handler)
     } {
-        std::mem::forget(monitor);
+        let owned = MainThreadOnly((token, local_handler));
+        monitor.on_shutdown(move || {
+            let (token, _handler) = owned.into_inner();
+            unsafe { NSEvent::removeMonitor(&token) };
+        });
     }
 
-    // --- Poller: periodic fallback in case event monitors are blocked (e.g. missing accessibility permission) ---
-    // This poller is the primary hover detection mechanism and runs continuously
+    // --- Poller: fallback in case event monitors are blocked (e.g. missing accessibility permission) ---
+    // Backs off far from the zones and drops to a heartbeat while the monitors deliver
     let st_poll = st.clone();
     let app_handle_poll = app.clone();
     let hover_zone_poll = hover_zone.clone();
-    let poll_interval = config::NotchConfig::get().hover.poll_interval_ms.value;
-    thread::spawn(move || loop {
-        thread::sleep(Duration::from_millis(poll_interval));
+    let zone_distance = Arc::new(zone_distance);
+    let debounce_poll = debounce.clone();
+    let poller = thread::spawn(move || loop {
+        let interval = poll
+            .lock()
+            .map(|poll| poll.interval())
+            .unwrap_or(hover::schedule::MONITOR_HEARTBEAT);
+        if !switch.sleep(interval) {
+            break;
+        }
+        if switch.is_paused() {
+            continue;
+        }
         let st_for_call = st_poll.clone();
         let hover_zone_for_call = hover_zone_poll.clone();
+        let zone_distance_for_call = zone_distance.clone();
         let app_for_call = app_handle_poll.clone();
+        let debounce_for_call = debounce_poll.clone();
+        let poll_for_call = poll.clone();
         let _ = app_handle_poll.run_on_main_thread(move || {
-            handle_mouse_move(&st_for_call, &app_for_call, &*hover_zone_for_call);
+            let p = handle_mouse_move(&st_for_call, &debounce_for_call, &app_for_call, &*hover_zone_for_call);
+            let distance = zone_distance_for_call(p);
+            if let Ok(mut poll) = poll_for_call.lock() {
+                poll.record_poll(Instant::now(), (p.x, p.y), distance);
+            }
         });
     });
+    monitor.add_thread(poller);
+    monitor.set_backend("macos");
+}
+
+// Event monitor tokens and their handler blocks are only touched on the main
+// thread: created in setup, removed on exit
+#[cfg(target_os = "macos")]
+struct MainThreadOnly<T>(T);
+#[cfg(target_os = "macos")]
+unsafe impl<T> Send for MainThreadOnly<T> {}
+
+#[cfg(target_os = "macos")]
+impl<T> MainThreadOnly<T> {
+    // Taking `self` whole keeps closures from capturing the non-Send fields
+    fn into_inner(self) -> T {
+        self.0
+    }
 }
 
 // Get config values
@@ -768,105 +580,157 @@ fn get_notch_config() -> config::NotchConfig {
     config::NotchConfig::get().clone()
 }
 
-// Swift-based notch manager commands
-#[tauri::command]
-async fn init_swift_notch(
-    app: tauri::AppHandle,
-    closed_w: f64,
-    closed_h: f64,
-    expanded_w: f64,
-    expanded_h: f64,
-    corner: f64,
-) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        macos::swift_bridge::init_notch_manager(&app, closed_w, closed_h, expanded_w, expanded_h, corner)
-    }
-    #[cfg(not(target_os = "macos"))]
-    {
-        Ok(())
-    }
+// Expand/collapse animation, through the platform's NotchController
+#[cfg(target_os = "macos")]
+fn create_controller(
+    app: &tauri::AppHandle,
+    window: tauri::WebviewWindow,
+) -> controller::SharedController {
+    Arc::new(macos::native_mask::MacNotchController::new(app, window))
 }
 
-#[tauri::command]
-async fn notch_force_expand() {
-    #[cfg(target_os = "macos")]
-    {
-        macos::swift_bridge::force_expand();
-    }
+#[cfg(not(target_os = "macos"))]
+fn create_controller(
+    app: &tauri::AppHandle,
+    window: tauri::WebviewWindow,
+) -> controller::SharedController {
+    Arc::new(controller::WindowController::new(app, window))
 }
 
-#[tauri::command]
-async fn notch_force_collapse() {
-    #[cfg(target_os = "macos")]
-    {
-        macos::swift_bridge::force_collapse();
+fn notch_controller(app: &tauri::AppHandle) -> Result<controller::SharedController, String> {
+    app.try_state::<controller::SharedController>()
+        .map(|controller| controller.inner().clone())
+        .ok_or_else(|| "notch controller unavailable".to_string())
+}
+
+// Starts a transition; its end is reported by the UI like any other
+fn run_transition(app: &tauri::AppHandle, stage: NotchStage) -> Result<(), String> {
+    let controller = notch_controller(app)?;
+    if stage != NotchStage::Peek {
+        app.state::<PeekTimer>().cancel();
     }
+    let store = app.state::<NotchStore>();
+    if controller::transition(&*controller, &store, stage, state::emit_to(app))? {
+        pin_changed(app, false);
+    }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct NotchAttachment {
+    // When set, the UI leaves the window size to the controller
+    resizes_window: bool,
 }
 
-// Legacy commands kept for backward compatibility (now using swift_bridge)
 #[tauri::command]
 async fn notch_attach(
-    window: tauri::Window,
     app: tauri::AppHandle,
-    _label: String,
     closed_w: f64,
     closed_h: f64,
     expanded_w: f64,
     expanded_h: f64,
+    peek_w: f64,
+    peek_h: f64,
     corner: f64,
-) {
-    #[cfg(target_os = "macos")]
-    {
-        macos::native_mask::attach_animator(&app, &window, closed_w, closed_h, expanded_w, expanded_h, corner);
-    }
+) -> Result<NotchAttachment, String> {
+    let notch = notch_controller(&app)?;
+    notch.attach(controller::NotchFrames {
+        closed_width: closed_w,
+        closed_height: closed_h,
+        peek_width: peek_w,
+        peek_height: peek_h,
+        expanded_width: expanded_w,
+        expanded_height: expanded_h,
+        corner_radius: corner,
+    })?;
+    Ok(NotchAttachment {
+        resizes_window: notch.resizes_window(),
+    })
 }
 
 #[tauri::command]
-async fn notch_expand(app: tauri::AppHandle) {
-    #[cfg(target_os = "macos")]
-    {
-        macos::native_mask::expand(&app);
-    }
+async fn notch_expand(app: tauri::AppHandle) -> Result<(), String> {
+    run_transition(&app, NotchStage::Expanded)
 }
 
 #[tauri::command]
-async fn notch_collapse(app: tauri::AppHandle) {
-    #[cfg(target_os = "macos")]
-    {
-        macos::native_mask::collapse(&app);
-    }
+async fn notch_collapse(app: tauri::AppHandle) -> Result<(), String> {
+    run_transition(&app, NotchStage::Compact)
 }
 
+// Briefly shows transient info, e.g. a new track, then returns to compact.
+// Shrinks an expanded capsule too, unless it's pinned. Peeking again restarts
+// the timer.
 #[tauri::command]
-async fn notch_set_progress(progress: f64) {
-    #[cfg(target_os = "macos")]
-    {
-        macos::native_mask::set_progress(progress);
+async fn notch_peek(app: tauri::AppHandle) -> Result<(), String> {
+    if app.state::<NotchStore>().get().pinned {
+        return Err("notch is pinned expanded".into());
     }
+    run_transition(&app, NotchStage::Peek)?;
+    let dismiss_ms = config::NotchConfig::get().peek.dismiss_ms.value;
+    let handle = app.clone();
+    app.state::<PeekTimer>()
+        .start(std::time::Duration::from_millis(dismiss_ms), move || {
+            let Ok(controller) = notch_controller(&handle) else {
+                return;
+            };
+            let store = handle.state::<NotchStore>();
+            match peek::dismiss(&*controller, &store, state::emit_to(&handle)) {
+                Ok(true) => pin_changed(&handle, false),
+                Ok(false) => {}
+                Err(e) => eprintln!("Peek dismiss failed: {}", e),
+            }
+        });
+    Ok(())
+}
+
+#[tauri::command]
+async fn notch_set_progress(progress: f64, app: tauri::AppHandle) -> Result<(), String> {
+    notch_controller(&app)?.set_progress(progress)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .invoke_handler(tauri::generate_handler![
-            get_notch_dimensions,
+            display::get_notch_dimensions,
             get_notch_config,
+            silhouette::get_notch_silhouette,
             ensure_accessibility,
+            get_notch_state,
             set_notch_expanded,
+            set_notch_phase,
+            set_notch_view,
+            hover_poll_stats,
+            pause_hover,
+            resume_hover,
+            hover_status,
+            notch_click,
+            notch_pointer,
+            set_notch_pinned,
+            get_notch_pinned,
             set_capsule_focus,
-            get_current_media,
-            get_media_artwork,
-            media_play_pause,
-            media_next_track,
-            media_previous_track,
-            media_seek,
-            init_swift_notch,
-            notch_force_expand,
-            notch_force_collapse,
+            media::get_current_media,
+            media::get_media_artwork,
+            media::media_play_pause,
+            media::media_next_track,
+            media::media_previous_track,
+            media::media_seek,
+            media::media_set_rating,
+            media::media_toggle_loved,
+            media::media_raise_player,
+            media::get_media_queue,
+            media::get_media_playlists,
+            media::media_play_queue_entry,
+            media::media_play_playlist,
+            media::sleep_timer::media_sleep_timer_start,
+            media::sleep_timer::media_sleep_timer_end_of_track,
+            media::sleep_timer::media_sleep_timer_cancel,
+            media::sleep_timer::get_sleep_timer,
             notch_attach,
             notch_expand,
             notch_collapse,
+            notch_peek,
             notch_set_progress
         ])
         .setup(|app| {
@@ -874,19 +738,63 @@ pub fn run() {
             {
                 app.handle().plugin(tauri_plugin_positioner::init())?;
             }
+            app.manage(audio::SpectrumState::default());
+            app.manage(media::sleep_timer::SleepTimerState::default());
+            app.manage(NotchStore::default());
+            app.manage(HoverState::new(app.state::<NotchStore>().pinned()));
+            app.manage(PeekTimer::default());
+            // Restored before the UI loads; it asks with get_notch_state and opens
+            if pin::load(app.handle()) {
+                set_pinned(app.handle(), true);
+            }
+            if let Some(win) = app.get_webview_window("notch-capsule") {
+                refresh_display(app.handle(), win.current_monitor().ok().flatten());
+                app.manage(create_controller(app.handle(), win));
+            }
             #[cfg(all(desktop, target_os = "macos"))]
             {
                 if let Some(win) = app.get_webview_window("notch-capsule") {
                     elevate_to_status_bar(&win)?;
                 }
-                app.manage(HoverState::default());
                 let handle = app.handle();
-                let expanded_flag = app.state::<HoverState>().expanded.clone();
-                start_hover_monitors(&handle, expanded_flag);
+                let hover_state = app.state::<HoverState>();
+                start_hover_monitors(
+                    &handle,
+                    app.state::<NotchStore>().zone_stage(),
+                    hover_state.debounce.clone(),
+                    hover_state.poll.clone(),
+                    &hover_state.monitor,
+                );
+            }
+            #[cfg(target_os = "linux")]
+            {
+                let hover_state = app.state::<HoverState>();
+                hover::linux::start(
+                    app.handle(),
+                    app.state::<NotchStore>().zone_stage(),
+                    hover_state.debounce.clone(),
+                    hover_state.poll.clone(),
+                    &hover_state.monitor,
+                );
+            }
+            // No native pointer source; only the debounce timer for webview crossings
+            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+            {
+                let hover_state = app.state::<HoverState>();
+                hover_state
+                    .monitor
+                    .add_thread(hover_state.debounce.spawn_timer(app.handle()));
             }
             Ok(())
         })
+        .on_window_event(capsule_window_event)
         .enable_macos_default_menu(false)
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Stop pollers and remove event monitors before the process goes away
+            if let tauri::RunEvent::Exit = event {
+                app.state::<HoverState>().monitor.shutdown();
+            }
+        });
 }
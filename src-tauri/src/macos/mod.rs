@@ -1,5 +1,2 @@
 #[cfg(target_os = "macos")]
 pub mod native_mask;
-
-#[cfg(target_os = "macos")]
-pub mod swift_bridge;
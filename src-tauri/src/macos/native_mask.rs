@@ -1,301 +1,412 @@
 #![cfg(target_os = "macos")]
-use cocoa::appkit::NSWindow;
 use cocoa::base::{id, nil};
 use cocoa::foundation::{NSPoint, NSRect, NSSize};
-use objc::{msg_send, sel, sel_impl, class};
-use tauri::{AppHandle, Emitter, Manager, Window};
-use std::sync::Mutex;
+use objc::runtime::{BOOL, NO, YES};
+use objc::{msg_send, sel, sel_impl};
+use tauri::{AppHandle, WebviewWindow};
+use std::sync::{mpsc::channel, Arc, Mutex};
 use std::os::raw::{c_char, c_int, c_void};
 use crate::config;
+use crate::controller::{notify_anim_end, ControllerTracker, NotchController, NotchFrames, NotchPosition};
+use crate::state::NotchStage;
 
 // Wrapper to make id Send-safe (safe because we only access on main thread)
-struct SendId(Option<id>);
+#[derive(Clone, Copy)]
+struct SendId(id);
 unsafe impl Send for SendId {}
 
-static ANIMATOR: Mutex<SendId> = Mutex::new(SendId(None));
-
-unsafe fn nswindow_from_tauri(window: &Window) -> Option<id> {
-    window.ns_window().ok().map(|w| w as id)
+/// Drives the Swift `NotchAnimator`, which animates a mask over the capsule
+/// window instead of resizing it. Objective-C calls all happen on the main thread.
+pub struct MacNotchController {
+    app: AppHandle,
+    window: WebviewWindow,
+    // Swift calls back with this pointer when an animation ends, so it has to
+    // live as long as the controller
+    callback_app: Box<AppHandle>,
+    animator: Arc<Mutex<Option<SendId>>>,
+    tracker: Mutex<ControllerTracker>,
 }
 
-pub fn attach_animator(
-    app: &AppHandle, 
-    window: &Window, 
-    closed_w: f64, 
-    closed_h: f64, 
-    expanded_w: f64, 
-    expanded_h: f64, 
-    corner: f64
-) {
-    let app = app.clone();
-    let window = window.clone();
-    
-    // Objective-C window operations must happen on the main thread
-    let result = app.run_on_main_thread(move || {
-        unsafe {
-            let ns_win = match nswindow_from_tauri(&window) { 
-                Some(w) => w, 
-                None => {
-                    eprintln!("Failed to get NSWindow from Tauri window");
-                    return;
-                }
-            };
-            
-            // Explicitly load the Swift framework dylib
-            use std::ffi::CString;
-            use std::env;
-            
-            // Declare dlopen/dlerror from libSystem
-            extern "C" {
-                fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
-                fn dlerror() -> *const c_char;
-            }
-            const RTLD_LAZY: c_int = 0x1;
-            const RTLD_GLOBAL: c_int = 0x8;
-            
-            // Get the actual executable path
-            let exe_path = env::current_exe().ok();
-            let exe_dir = exe_path.as_ref().and_then(|p| p.parent());
-            
-            let mut paths = vec![];
-            
-            // Add paths relative to executable directory
-            if let Some(dir) = exe_dir {
-                paths.push(dir.join("libNotchCapsuleKit.dylib"));
-                // In dev mode, the dylib is in target/debug
-                if let Some(parent) = dir.parent() {
-                    paths.push(parent.join("libNotchCapsuleKit.dylib"));
-                }
-            }
-            
-            // Try current working directory
-            if let Ok(cwd) = env::current_dir() {
-                paths.push(cwd.join("target/debug/libNotchCapsuleKit.dylib"));
-                paths.push(cwd.join("target/release/libNotchCapsuleKit.dylib"));
-            }
-            
-            let mut loaded = false;
-            for path in &paths {
-                if path.exists() {
-                    if let Some(path_str) = path.to_str() {
-                        if let Ok(dylib_path) = CString::new(path_str) {
-                            let handle = dlopen(dylib_path.as_ptr(), RTLD_LAZY | RTLD_GLOBAL);
-                            if !handle.is_null() {
-                                eprintln!("Successfully loaded Swift dylib from: {}", path_str);
-                                loaded = true;
-                                break;
-                            } else {
-                                let error = dlerror();
-                                if !error.is_null() {
-                                    let error_str = std::ffi::CStr::from_ptr(error).to_string_lossy();
-                                    eprintln!("Failed to load {}: {}", path_str, error_str);
-                                }
-                            }
-                        }
-                    }
+impl MacNotchController {
+    pub fn new(app: &AppHandle, window: WebviewWindow) -> Self {
+        Self {
+            app: app.clone(),
+            window,
+            callback_app: Box::new(app.clone()),
+            animator: Arc::new(Mutex::new(None)),
+            tracker: Mutex::new(ControllerTracker::default()),
+        }
+    }
+
+    fn callback_ptr(&self) -> usize {
+        &*self.callback_app as *const AppHandle as usize
+    }
+
+    // The frames and where the capsule was, if it has to move at all
+    fn move_to(&self, position: NotchPosition) -> Result<Option<(NotchFrames, NotchPosition)>, String> {
+        self.tracker
+            .lock()
+            .map_err(|_| "controller state poisoned".to_string())?
+            .move_to(position)
+    }
+
+    // Runs `f` with the animator on the main thread, if one is attached
+    fn with_animator(&self, f: impl FnOnce(id) + Send + 'static) -> Result<(), String> {
+        let animator = self.animator.clone();
+        self.app
+            .run_on_main_thread(move || {
+                let current = animator.lock().ok().and_then(|guard| *guard);
+                if let Some(SendId(animator)) = current {
+                    f(animator);
                 }
+            })
+            .map_err(|e| format!("failed to schedule notch animation: {e:?}"))
+    }
+}
+
+impl NotchController for MacNotchController {
+    fn attach(&self, frames: NotchFrames) -> Result<(), String> {
+        let position = {
+            let mut tracker = self
+                .tracker
+                .lock()
+                .map_err(|_| "controller state poisoned".to_string())?;
+            if !tracker.attach(frames) {
+                return Ok(());
             }
-            
-            if !loaded {
-                eprintln!("Warning: Could not explicitly load Swift dylib, relying on linker");
-                eprintln!("Tried paths: {:?}", paths.iter().map(|p| p.to_string_lossy().to_string()).collect::<Vec<_>>());
-            }
-            
-            // Load NotchAnimator class from the Swift framework
-            // Use objc_getClass as a direct approach
-            extern "C" {
-                fn objc_getClass(name: *const c_char) -> id;
+            tracker.position()
+        };
+
+        let window = self.window.clone();
+        let animator = self.animator.clone();
+        let (tx, rx) = channel();
+        self.app
+            .run_on_main_thread(move || {
+                let result = unsafe { attach_on_main_thread(&window, &animator, frames, position) };
+                let _ = tx.send(result);
+            })
+            .map_err(|e| format!("failed to schedule attach: {e:?}"))?;
+
+        let result = rx
+            .recv()
+            .map_err(|_| "attach channel dropped".to_string())
+            .and_then(|result| result);
+        if result.is_err() {
+            // Let the next attach try again
+            if let Ok(mut tracker) = self.tracker.lock() {
+                tracker.detach();
             }
-            
-            let class_names = vec![
-                "NotchCapsuleKit.NotchAnimator\0",
-                "NotchAnimator\0",
-                "_TtC15NotchCapsuleKit13NotchAnimator\0", // Mangled Swift name
+        }
+        result
+    }
+
+    fn show(&self, stage: NotchStage) -> Result<(), String> {
+        let to = NotchPosition::Stage(stage);
+        let Some((frames, from)) = self.move_to(to)? else {
+            return Ok(());
+        };
+        let (width, height) = frames.size(to);
+        let growing = frames.grows(from, to);
+        let app_ptr = self.callback_ptr();
+        self.with_animator(move |animator| unsafe {
+            // Swift picks the solved expand or collapse curve by `growing`,
+            // and hands `stage` back when the animation ends
+            let app_ptr = app_ptr as *mut c_void;
+            let growing: BOOL = if growing { YES } else { NO };
+            let stage = stage as i32;
+            let _: () = msg_send![
+                animator,
+                animateToWidth:width
+                height:height
+                growing:growing
+                stage:stage
+                appHandle:app_ptr
             ];
-            
-            let mut cls: id = nil;
-            for name in &class_names {
-                let c_name = CString::new(&name[..name.len()-1]).unwrap();
-                cls = objc_getClass(c_name.as_ptr());
-                if cls != nil {
-                    eprintln!("Found class with name: {}", &name[..name.len()-1]);
-                    break;
-                }
-            }
-            
-            if cls == nil {
-                eprintln!("Failed to find NotchAnimator class after trying multiple names");
-                return;
-            }
-            
-            let animator: id = msg_send![cls, new];
-            if animator == nil {
-                eprintln!("Failed to create NotchAnimator instance");
-                return;
-            }
-            
-            // Verify the selector exists before calling
-            extern "C" {
-                fn sel_registerName(str: *const c_char) -> objc::runtime::Sel;
-                fn class_respondsToSelector(cls: id, sel: objc::runtime::Sel) -> bool;
-            }
-            
-            let attach_sel = sel_registerName(b"attachTo:closedRect:expandedRect:corner:\0".as_ptr() as *const c_char);
-            if !class_respondsToSelector(cls, attach_sel) {
-                eprintln!("NotchAnimator does not respond to attachTo:closedRect:expandedRect:corner:");
-                eprintln!("Trying alternative selectors...");
-                
-                // Try without module prefix in selector
-                let alt_sel = sel_registerName(b"attach:\0".as_ptr() as *const c_char);
-                if !class_respondsToSelector(cls, alt_sel) {
-                    eprintln!("Class does not respond to any attach selector");
-                    return;
-                }
-            }
-            
-            let closed_rect = NSRect::new(
-                NSPoint::new(0., 0.),
-                NSSize::new(closed_w, closed_h)
-            );
-            let expanded_rect = NSRect::new(
-                NSPoint::new(0., 0.),
-                NSSize::new(expanded_w, expanded_h)
-            );
-            
-            eprintln!("Calling attachTo:closedRect:expandedRect:corner: on animator");
-            // Use catch_unwind to prevent panics from crashing the app
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                let _: () = msg_send![
-                    animator, 
-                    attachTo:ns_win 
-                    closedRect:closed_rect
-                    expandedRect:expanded_rect
-                    corner:corner
-                ];
-            }));
-            
-            match result {
-                Ok(_) => {
-                    eprintln!("Successfully attached animator to window");
+        })
+    }
+
+    fn set_progress(&self, progress: f64) -> Result<(), String> {
+        if self.move_to(NotchPosition::Progress(progress))?.is_none() {
+            return Ok(());
+        }
+        let progress = progress.clamp(0.0, 1.0);
+        self.with_animator(move |animator| unsafe {
+            let _: () = msg_send![animator, setProgress:progress];
+        })
+    }
+
+    fn resize_expanded(&self, width: f64, height: f64) -> Result<(), String> {
+        let (resized, position) = {
+            let mut tracker = self
+                .tracker
+                .lock()
+                .map_err(|_| "controller state poisoned".to_string())?;
+            (tracker.resize_expanded(width, height)?, tracker.position())
+        };
+        let Some((before, after)) = resized else {
+            return Ok(());
+        };
+        let growing: BOOL = if before.expanded_grows(&after) { YES } else { NO };
+        let app_ptr = self.callback_ptr();
+        self.with_animator(move |animator| unsafe {
+            let _: () = msg_send![animator, setExpandedWidth:width height:height];
+            match position {
+                NotchPosition::Stage(NotchStage::Expanded) => {
+                    let app_ptr = app_ptr as *mut c_void;
+                    let stage = NotchStage::Expanded as i32;
+                    let _: () = msg_send![
+                        animator,
+                        animateToWidth:width
+                        height:height
+                        growing:growing
+                        stage:stage
+                        appHandle:app_ptr
+                    ];
                 }
-                Err(_) => {
-                    eprintln!("Panic occurred while calling attachTo method");
-                    return;
+                NotchPosition::Progress(progress) => {
+                    let _: () = msg_send![animator, setProgress:progress];
                 }
+                // Applies from the next expand
+                NotchPosition::Stage(_) => {}
             }
-            
-            if let Ok(mut guard) = ANIMATOR.lock() {
-                guard.0 = Some(animator);
-            }
-            
-            // Send config to Swift via JSON string - wrapped to catch any exceptions
-            let cfg = config::NotchConfig::get();
-            if let Ok(config_json) = serde_json::to_string(cfg) {
-                if let Ok(config_cstr) = std::ffi::CString::new(config_json) {
-                    // Check if selector exists before calling
-                    extern "C" {
-                        fn sel_registerName(str: *const c_char) -> objc::runtime::Sel;
-                        fn class_respondsToSelector(cls: id, sel: objc::runtime::Sel) -> bool;
-                    }
-                    
-                    let set_config_sel = sel_registerName(b"setConfigJson:\0".as_ptr() as *const c_char);
-                    let animator_class: id = msg_send![animator, class];
-                    
-                    if class_respondsToSelector(animator_class, set_config_sel) {
-                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            let _: () = msg_send![animator, setConfigJson:config_cstr.as_ptr()];
-                        }));
-                        
-                        match result {
-                            Ok(_) => eprintln!("Successfully sent config to Swift animator"),
-                            Err(_) => eprintln!("Error sending config to Swift animator, using defaults"),
-                        }
-                    } else {
-                        eprintln!("Swift animator doesn't have setConfigJson method, using defaults");
-                    }
-                } else {
-                    eprintln!("Failed to create C string from config JSON");
-                }
-            } else {
-                eprintln!("Failed to serialize config to JSON");
-            }
-        }
-    });
-    
-    if let Err(e) = result {
-        eprintln!("Failed to run attach_animator on main thread: {:?}", e);
+        })
     }
 }
 
-pub fn expand(app: &AppHandle) {
-    let app_clone = app.clone();
-    
-    // Objective-C window operations must happen on the main thread
-    let _ = app.run_on_main_thread(move || {
-        unsafe {
-            if let Ok(guard) = ANIMATOR.lock() {
-                if let Some(animator) = guard.0 {
-                    // Duration parameter is passed but overridden by Swift constants
-                    // matching Boring Notch animation timing
-                    let duration: f64 = 0.30;
-                    let app_ptr = &app_clone as *const _ as *mut std::ffi::c_void;
-                    let _: () = msg_send![animator, expandWithDuration:duration appHandle:app_ptr];
-                }
-            }
+unsafe fn attach_on_main_thread(
+    window: &WebviewWindow,
+    animator: &Mutex<Option<SendId>>,
+    frames: NotchFrames,
+    position: NotchPosition,
+) -> Result<(), String> {
+    let ns_win = match window.ns_window() {
+        Ok(w) if !w.is_null() => w as id,
+        _ => return Err("Failed to get NSWindow from Tauri window".into()),
+    };
+
+    let existing = animator.lock().ok().and_then(|guard| *guard);
+    let instance = match existing {
+        Some(SendId(instance)) => instance,
+        None => {
+            let instance = create_animator()?;
+            send_config(instance);
+            instance
+        }
+    };
+
+    let closed_rect = NSRect::new(
+        NSPoint::new(0., 0.),
+        NSSize::new(frames.closed_width, frames.closed_height)
+    );
+    let expanded_rect = NSRect::new(
+        NSPoint::new(0., 0.),
+        NSSize::new(frames.expanded_width, frames.expanded_height)
+    );
+    let corner = frames.corner_radius;
+
+    eprintln!("Calling attachTo:closedRect:expandedRect:corner: on animator");
+    // Use catch_unwind to prevent panics from crashing the app
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _: () = msg_send![
+            instance,
+            attachTo:ns_win
+            closedRect:closed_rect
+            expandedRect:expanded_rect
+            corner:corner
+        ];
+        // Attaching resets the mask to closed
+        if position != NotchPosition::Stage(NotchStage::Compact) {
+            let (width, height) = frames.size(position);
+            let _: () = msg_send![instance, setWidth:width height:height];
         }
-    });
+    }));
+    if result.is_err() {
+        return Err("Panic occurred while calling attachTo method".into());
+    }
+    eprintln!("Successfully attached animator to window");
+
+    if let Ok(mut guard) = animator.lock() {
+        *guard = Some(SendId(instance));
+    }
+    Ok(())
 }
 
-pub fn collapse(app: &AppHandle) {
-    let app_clone = app.clone();
-    
-    // Objective-C window operations must happen on the main thread
-    let _ = app.run_on_main_thread(move || {
-        unsafe {
-            if let Ok(guard) = ANIMATOR.lock() {
-                if let Some(animator) = guard.0 {
-                    // Duration parameter is passed but overridden by Swift constants
-                    // matching Boring Notch animation timing
-                    let duration: f64 = 0.22;
-                    let app_ptr = &app_clone as *const _ as *mut std::ffi::c_void;
-                    let _: () = msg_send![animator, collapseWithDuration:duration appHandle:app_ptr];
+// Loads the Swift framework and creates a NotchAnimator
+unsafe fn create_animator() -> Result<id, String> {
+    // Explicitly load the Swift framework dylib
+    use std::ffi::CString;
+    use std::env;
+
+    // Declare dlopen/dlerror from libSystem
+    extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlerror() -> *const c_char;
+    }
+    const RTLD_LAZY: c_int = 0x1;
+    const RTLD_GLOBAL: c_int = 0x8;
+
+    // Get the actual executable path
+    let exe_path = env::current_exe().ok();
+    let exe_dir = exe_path.as_ref().and_then(|p| p.parent());
+
+    let mut paths = vec![];
+
+    // Add paths relative to executable directory
+    if let Some(dir) = exe_dir {
+        paths.push(dir.join("libNotchCapsuleKit.dylib"));
+        paths.push(dir.join("../Resources/target/release/libNotchCapsuleKit.dylib"));
+        // In dev mode, the dylib is in target/debug
+        if let Some(parent) = dir.parent() {
+            paths.push(parent.join("libNotchCapsuleKit.dylib"));
+        }
+    }
+
+    // Try current working directory
+    if let Ok(cwd) = env::current_dir() {
+        paths.push(cwd.join("target/debug/libNotchCapsuleKit.dylib"));
+        paths.push(cwd.join("target/release/libNotchCapsuleKit.dylib"));
+    }
+
+    let mut loaded = false;
+    for path in &paths {
+        if path.exists() {
+            if let Some(path_str) = path.to_str() {
+                if let Ok(dylib_path) = CString::new(path_str) {
+                    let handle = dlopen(dylib_path.as_ptr(), RTLD_LAZY | RTLD_GLOBAL);
+                    if !handle.is_null() {
+                        eprintln!("Successfully loaded Swift dylib from: {}", path_str);
+                        loaded = true;
+                        break;
+                    } else {
+                        let error = dlerror();
+                        if !error.is_null() {
+                            let error_str = std::ffi::CStr::from_ptr(error).to_string_lossy();
+                            eprintln!("Failed to load {}: {}", path_str, error_str);
+                        }
+                    }
                 }
             }
         }
-    });
+    }
+
+    if !loaded {
+        eprintln!("Warning: Could not explicitly load Swift dylib, relying on linker");
+        eprintln!("Tried paths: {:?}", paths.iter().map(|p| p.to_string_lossy().to_string()).collect::<Vec<_>>());
+    }
+
+    // Load NotchAnimator class from the Swift framework
+    // Use objc_getClass as a direct approach
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> id;
+        fn sel_registerName(str: *const c_char) -> objc::runtime::Sel;
+        fn class_respondsToSelector(cls: id, sel: objc::runtime::Sel) -> bool;
+    }
+
+    let class_names = vec![
+        "NotchCapsuleKit.NotchAnimator\0",
+        "NotchAnimator\0",
+        "_TtC15NotchCapsuleKit13NotchAnimator\0", // Mangled Swift name
+    ];
+
+    let mut cls: id = nil;
+    for name in &class_names {
+        let c_name = CString::new(&name[..name.len()-1]).unwrap();
+        cls = objc_getClass(c_name.as_ptr());
+        if cls != nil {
+            eprintln!("Found class with name: {}", &name[..name.len()-1]);
+            break;
+        }
+    }
+
+    if cls == nil {
+        return Err("Failed to find NotchAnimator class after trying multiple names".into());
+    }
+
+    // Verify the selector exists before creating an instance
+    let attach_sel = sel_registerName(b"attachTo:closedRect:expandedRect:corner:\0".as_ptr() as *const c_char);
+    if !class_respondsToSelector(cls, attach_sel) {
+        return Err("NotchAnimator does not respond to attachTo:closedRect:expandedRect:corner:".into());
+    }
+
+    let animator: id = msg_send![cls, new];
+    if animator == nil {
+        return Err("Failed to create NotchAnimator instance".into());
+    }
+    Ok(animator)
 }
 
-pub fn set_progress(progress: f64) {
-    let animator_id = if let Ok(guard) = ANIMATOR.lock() {
-        guard.0
-    } else {
+// Send config to Swift via JSON string - wrapped to catch any exceptions
+unsafe fn send_config(animator: id) {
+    extern "C" {
+        fn sel_registerName(str: *const c_char) -> objc::runtime::Sel;
+        fn class_respondsToSelector(cls: id, sel: objc::runtime::Sel) -> bool;
+    }
+
+    let cfg = config::NotchConfig::get();
+    let Ok(config_json) = serde_json::to_string(cfg) else {
+        eprintln!("Failed to serialize config to JSON");
         return;
     };
-    
-    if animator_id.is_none() {
+    let Ok(config_cstr) = std::ffi::CString::new(config_json) else {
+        eprintln!("Failed to create C string from config JSON");
         return;
-    }
-    
-    // Objective-C window operations must happen on the main thread
-    // Note: This might be called frequently, so we need to get an AppHandle somehow
-    // For now, we'll need to pass app handle or use a different approach
-    // Actually, setProgress might not need main thread if it's just updating a value
-    // But to be safe, let's check if we can do it without main thread first
-    unsafe {
-        if let Some(animator) = animator_id {
-            let _: () = msg_send![animator, setProgress:progress];
+    };
+
+    // Check if selector exists before calling
+    let set_config_sel = sel_registerName(b"setConfigJson:\0".as_ptr() as *const c_char);
+    let animator_class: id = msg_send![animator, class];
+
+    if class_respondsToSelector(animator_class, set_config_sel) {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _: () = msg_send![animator, setConfigJson:config_cstr.as_ptr()];
+        }));
+
+        match result {
+            Ok(_) => eprintln!("Successfully sent config to Swift animator"),
+            Err(_) => eprintln!("Error sending config to Swift animator, using defaults"),
         }
+    } else {
+        eprintln!("Swift animator doesn't have setConfigJson method, using defaults");
     }
 }
 
-// Called by Swift stub via dlsym; we emit the event here.
+// Called by Swift stub via dlsym with the stage passed to animateToWidth; we
+// emit the event here.
 #[no_mangle]
-pub extern "C" fn _notch_notify_anim_end(app_ptr: *mut std::ffi::c_void, phase: i32) {
-    if app_ptr.is_null() { 
-        return; 
+pub extern "C" fn _notch_notify_anim_end(app_ptr: *mut std::ffi::c_void, stage: i32) {
+    if app_ptr.is_null() {
+        return;
     }
-    
+
+    // Points at MacNotchController::callback_app
     let app: &AppHandle = unsafe { &*(app_ptr as *const AppHandle) };
-    let payload = if phase == 0 { "expand" } else { "collapse" };
-    let _ = app.emit("notch-native-anim-end", serde_json::json!({"phase": payload}));
+    let stage = match stage {
+        1 => NotchStage::Peek,
+        2 => NotchStage::Expanded,
+        _ => NotchStage::Compact,
+    };
+    notify_anim_end(app, stage);
+}
+
+// Called by Swift via dlsym for every mask path, so the mask is the same
+// silhouette the CSS masks and hover zones use. Writes up to `capacity` points
+// as x, y pairs (y-down) into `out` and returns how many the outline has.
+#[no_mangle]
+pub extern "C" fn _notch_silhouette_polygon(
+    width: f64,
+    height: f64,
+    corner: f64,
+    progress: f64,
+    out: *mut f64,
+    capacity: usize,
+) -> usize {
+    let polygon = crate::silhouette::Silhouette::new(width, height, corner, progress).polygon();
+    if !out.is_null() {
+        let out = unsafe { std::slice::from_raw_parts_mut(out, capacity * 2) };
+        for (slot, (x, y)) in out.chunks_exact_mut(2).zip(&polygon) {
+            slot[0] = *x;
+            slot[1] = *y;
+        }
+    }
+    polygon.len()
 }
@@ -0,0 +1,675 @@
+use super::{sources, MediaBackend, MediaInfo, Playlist, QueueEntry, QUEUE_LIMIT};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
+
+/// Talks to Music and Spotify (and the system Now Playing center) through `osascript`.
+pub struct AppleScriptBackend;
+
+impl MediaBackend for AppleScriptBackend {
+    // Now Playing info from macOS (works with ALL apps that report to Now Playing)
+    fn now_playing(&self) -> Option<MediaInfo> {
+        use std::process::Command;
+
+        // Use AppleScript to get Now Playing info from macOS system-wide
+        // This works with Music, Spotify, Chrome, Safari, VLC, and ANY app that reports to Now Playing
+        // Sources `media.sources` filters out are skipped, so the next one shows instead
+        let Players {
+            music,
+            spotify,
+            system,
+        } = players();
+        let min_duration = sources::filter().min_duration();
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!(r#"
+                use framework "Foundation"
+                use framework "MediaPlayer"
+                use scripting additions
+            
+                try
+                    -- Get Now Playing info from system
+                    set infoCenter to current application's MPNowPlayingInfoCenter's defaultCenter()
+                    set nowPlayingInfo to infoCenter's nowPlayingInfo()
+                
+                    if {system} and nowPlayingInfo is not missing value then
+                        set titleKey to current application's MPMediaItemPropertyTitle
+                        set artistKey to current application's MPMediaItemPropertyArtist
+                        set albumKey to current application's MPMediaItemPropertyAlbumTitle
+                        set durationKey to current application's MPMediaItemPropertyPlaybackDuration
+                        set elapsedKey to current application's MPNowPlayingInfoPropertyElapsedPlaybackTime
+                        set rateKey to current application's MPNowPlayingInfoPropertyPlaybackRate
+                    
+                        set trackTitle to (nowPlayingInfo's objectForKey:titleKey) as text
+                        set trackArtist to (nowPlayingInfo's objectForKey:artistKey) as text
+                        set trackAlbum to (nowPlayingInfo's objectForKey:albumKey) as text
+                        set trackDuration to (nowPlayingInfo's objectForKey:durationKey) as real
+                        set trackElapsed to (nowPlayingInfo's objectForKey:elapsedKey) as real
+                        set playbackRate to (nowPlayingInfo's objectForKey:rateKey) as real
+                    
+                        set isPlaying to (playbackRate > 0)
+                    
+                        if trackDuration <= 0 or trackDuration >= {min_duration} then
+                            return trackTitle & "|||" & trackArtist & "|||" & trackAlbum & "|||" & trackDuration & "|||" & trackElapsed & "|||" & isPlaying & "|||" & (current date)
+                        end if
+                    end if
+                end try
+            
+                -- Fallback: Try common music apps if Now Playing API fails
+                try
+                    tell application "System Events"
+                        set musicRunning to (name of processes) contains "Music"
+                    end tell
+                
+                    if {music} and musicRunning then
+                        tell application "Music"
+                            if player state is not stopped then
+                                set trackName to name of current track
+                                set artistName to artist of current track
+                                set albumName to album of current track
+                                set trackDuration to duration of current track
+                                set trackPosition to player position
+                                set isPlaying to (player state is playing)
+                                set trackRating to rating of current track
+                                -- "loved" became "favorited" in newer Music releases
+                                set trackLoved to false
+                                try
+                                    set trackLoved to favorited of current track
+                                on error
+                                    try
+                                        set trackLoved to loved of current track
+                                    end try
+                                end try
+                                if trackDuration <= 0 or trackDuration >= {min_duration} then
+                                    return trackName & "|||" & artistName & "|||" & albumName & "|||" & trackDuration & "|||" & trackPosition & "|||" & isPlaying & "|||" & (current date) & "|||" & trackRating & "|||" & trackLoved & "|||" & "Music"
+                                end if
+                            end if
+                        end tell
+                    end if
+                end try
+            
+                -- Try Spotify
+                try
+                    tell application "System Events"
+                        set spotifyRunning to (name of processes) contains "Spotify"
+                    end tell
+                
+                    if {spotify} and spotifyRunning then
+                        tell application "Spotify"
+                            if player state is not stopped then
+                                set trackName to name of current track
+                                set artistName to artist of current track
+                                set albumName to album of current track
+                                set trackDuration to duration of current track / 1000
+                                set trackPosition to player position
+                                set isPlaying to (player state is playing)
+                                if trackDuration <= 0 or trackDuration >= {min_duration} then
+                                    return trackName & "|||" & artistName & "|||" & albumName & "|||" & trackDuration & "|||" & trackPosition & "|||" & isPlaying & "|||" & (current date) & "|||" & "" & "|||" & false & "|||" & "Spotify"
+                                end if
+                            end if
+                        end tell
+                    end if
+                end try
+            
+                return ""
+            "#))
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !result.is_empty() {
+                let parts: Vec<&str> = result.split("|||").collect();
+                if parts.len() >= 6 {
+                    return Some(MediaInfo {
+                        title: parts[0].to_string(),
+                        artist: parts[1].to_string(),
+                        album: parts[2].to_string(),
+                        artwork_url: None, // Will fetch separately
+                        duration: parts[3].parse().unwrap_or(0.0),
+                        elapsed: parts[4].parse().unwrap_or(0.0),
+                        is_playing: parts[5] == "true",
+                        // Only Music reports these (rating is 0-100 there)
+                        rating: parts
+                            .get(7)
+                            .and_then(|r| r.parse::<f64>().ok())
+                            .map(|r| (r / 100.0).clamp(0.0, 1.0)),
+                        loved: parts.get(8) == Some(&"true"),
+                        can_rate: parts.get(9) == Some(&"Music"),
+                        // The Now Playing center doesn't say which app is playing
+                        player: parts
+                            .get(9)
+                            .filter(|p| !p.is_empty())
+                            .map(|p| p.to_string()),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    // Album artwork as base64 data URL (Music) or artwork URL (Spotify)
+    fn artwork(&self) -> Option<String> {
+        use std::process::Command;
+        let Players { music, spotify, .. } = players();
+
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!(r#"
+                try
+                    tell application "System Events"
+                        set musicRunning to (name of processes) contains "Music"
+                    end tell
+                
+                    if {music} and musicRunning then
+                        tell application "Music"
+                            if player state is not stopped then
+                                set artworkData to data of artwork 1 of current track
+                                return artworkData as «class PNGf»
+                            end if
+                        end tell
+                    end if
+                end try
+            
+                try
+                    tell application "System Events"
+                        set spotifyRunning to (name of processes) contains "Spotify"
+                    end tell
+                
+                    if {spotify} and spotifyRunning then
+                        tell application "Spotify"
+                            if player state is not stopped then
+                                return artwork url of current track
+                            end if
+                        end tell
+                    end if
+                end try
+            
+                return ""
+            "#))
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            let stdout = output.stdout;
+            if stdout.is_empty() {
+                return None;
+            }
+
+            match std::str::from_utf8(&stdout) {
+                Ok(text) => {
+                    let trimmed = text.trim();
+                    if trimmed.starts_with("http") && !trimmed.is_empty() && trimmed != "missing value" {
+                        return Some(trimmed.to_string());
+                    }
+                    return None;
+                }
+                Err(_) => {
+                    let data = if stdout.last() == Some(&b'\n') {
+                        &stdout[..stdout.len().saturating_sub(1)]
+                    } else {
+                        &stdout[..]
+                    };
+                    if !data.is_empty() {
+                        let encoded = BASE64_STANDARD.encode(data);
+                        if !encoded.is_empty() {
+                            return Some(format!("data:image/png;base64,{}", encoded));
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn play_pause(&self) -> bool {
+        use std::process::Command;
+        let Players { music, spotify, .. } = players();
+
+        // Try Music app
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!(r#"
+                tell application "System Events"
+                    set musicRunning to (name of processes) contains "Music"
+                end tell
+            
+                if {music} and musicRunning then
+                    tell application "Music"
+                        playpause
+                        return true
+                    end tell
+                end if
+            
+                -- Try Spotify
+                tell application "System Events"
+                    set spotifyRunning to (name of processes) contains "Spotify"
+                end tell
+            
+                if {spotify} and spotifyRunning then
+                    tell application "Spotify"
+                        playpause
+                        return true
+                    end tell
+                end if
+            
+                return false
+            "#))
+            .output()
+            .ok();
+
+        output.map(|o| o.status.success()).unwrap_or(false)
+    }
+
+    fn next_track(&self) -> bool {
+        use std::process::Command;
+        let Players { music, spotify, .. } = players();
+
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!(r#"
+                tell application "System Events"
+                    set musicRunning to (name of processes) contains "Music"
+                end tell
+            
+                if {music} and musicRunning then
+                    tell application "Music"
+                        next track
+                        return true
+                    end tell
+                end if
+            
+                tell application "System Events"
+                    set spotifyRunning to (name of processes) contains "Spotify"
+                end tell
+            
+                if {spotify} and spotifyRunning then
+                    tell application "Spotify"
+                        next track
+                        return true
+                    end tell
+                end if
+            
+                return false
+            "#))
+            .output()
+            .ok();
+
+        output.map(|o| o.status.success()).unwrap_or(false)
+    }
+
+    fn previous_track(&self) -> bool {
+        use std::process::Command;
+        let Players { music, spotify, .. } = players();
+
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!(r#"
+                tell application "System Events"
+                    set musicRunning to (name of processes) contains "Music"
+                end tell
+            
+                if {music} and musicRunning then
+                    tell application "Music"
+                        previous track
+                        return true
+                    end tell
+                end if
+            
+                tell application "System Events"
+                    set spotifyRunning to (name of processes) contains "Spotify"
+                end tell
+            
+                if {spotify} and spotifyRunning then
+                    tell application "Spotify"
+                        previous track
+                        return true
+                    end tell
+                end if
+            
+                return false
+            "#))
+            .output()
+            .ok();
+
+        output.map(|o| o.status.success()).unwrap_or(false)
+    }
+
+    fn seek(&self, position: f64) -> bool {
+        use std::process::Command;
+        let Players { music, spotify, .. } = players();
+
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                r#"
+                tell application "System Events"
+                    set musicRunning to (name of processes) contains "Music"
+                end tell
+            
+                if {music} and musicRunning then
+                    tell application "Music"
+                        set player position to {}
+                        return true
+                    end tell
+                end if
+            
+                tell application "System Events"
+                    set spotifyRunning to (name of processes) contains "Spotify"
+                end tell
+            
+                if {spotify} and spotifyRunning then
+                    tell application "Spotify"
+                        set player position to {}
+                        return true
+                    end tell
+                end if
+            
+                return false
+                "#,
+                position, position
+            ))
+            .output()
+            .ok();
+
+        output.map(|o| o.status.success()).unwrap_or(false)
+    }
+
+    fn set_rating(&self, rating: f64) -> bool {
+        if !players().music {
+            return false;
+        }
+        let level = (rating.clamp(0.0, 1.0) * 100.0).round() as i64;
+        osascript(&format!(
+            r#"
+            tell application "System Events"
+                set musicRunning to (name of processes) contains "Music"
+            end tell
+
+            if musicRunning then
+                tell application "Music"
+                    if player state is not stopped then
+                        set rating of current track to {level}
+                        return true
+                    end if
+                end tell
+            end if
+
+            return false
+            "#
+        ))
+        .is_some_and(|out| out == "true")
+    }
+
+    fn toggle_loved(&self) -> Option<bool> {
+        if !players().music {
+            return None;
+        }
+        let out = osascript(
+            r#"
+            tell application "System Events"
+                set musicRunning to (name of processes) contains "Music"
+            end tell
+
+            if musicRunning then
+                tell application "Music"
+                    if player state is not stopped then
+                        try
+                            set favorited of current track to not (favorited of current track)
+                            return favorited of current track
+                        on error
+                            set loved of current track to not (loved of current track)
+                            return loved of current track
+                        end try
+                    end if
+                end tell
+            end if
+
+            return ""
+            "#,
+        )?;
+        match out.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    // Music has no scriptable "Up Next"; the rest of the current playlist is the
+    // closest equivalent. Spotify exposes neither queue nor playlists.
+    fn queue(&self) -> Vec<QueueEntry> {
+        if !players().music {
+            return Vec::new();
+        }
+        let Some(out) = osascript(&format!(
+            r#"
+            tell application "System Events"
+                set musicRunning to (name of processes) contains "Music"
+            end tell
+
+            if musicRunning then
+                tell application "Music"
+                    if player state is not stopped then
+                        set currentId to persistent ID of current track
+                        set queueTracks to tracks of current playlist
+                        set startIndex to 1
+                        repeat with i from 1 to count of queueTracks
+                            if persistent ID of item i of queueTracks is currentId then
+                                set startIndex to i
+                                exit repeat
+                            end if
+                        end repeat
+                        set endIndex to startIndex + {limit} - 1
+                        if endIndex > (count of queueTracks) then set endIndex to count of queueTracks
+                        set out to ""
+                        repeat with i from startIndex to endIndex
+                            set t to item i of queueTracks
+                            set out to out & (persistent ID of t) & "|||" & (name of t) & "|||" & (artist of t) & "|||" & (duration of t) & "|||" & (i = startIndex) & linefeed
+                        end repeat
+                        return out
+                    end if
+                end tell
+            end if
+
+            return ""
+            "#,
+            limit = QUEUE_LIMIT
+        )) else {
+            return Vec::new();
+        };
+
+        out.lines()
+            .filter_map(|line| {
+                let parts: Vec<&str> = line.split("|||").collect();
+                (parts.len() >= 5).then(|| QueueEntry {
+                    id: parts[0].to_string(),
+                    title: parts[1].to_string(),
+                    artist: parts[2].to_string(),
+                    duration: parts[3].parse().unwrap_or(0.0),
+                    is_current: parts[4] == "true",
+                })
+            })
+            .collect()
+    }
+
+    fn play_queue_entry(&self, id: &str) -> bool {
+        let Some(id) = persistent_id(id) else {
+            return false;
+        };
+        if !players().music {
+            return false;
+        }
+        osascript(&format!(
+            r#"
+            tell application "Music"
+                play (first track of current playlist whose persistent ID is "{id}")
+                return true
+            end tell
+            "#
+        ))
+        .is_some_and(|out| out == "true")
+    }
+
+    fn playlists(&self) -> Vec<Playlist> {
+        if !players().music {
+            return Vec::new();
+        }
+        let Some(out) = osascript(
+            r#"
+            tell application "System Events"
+                set musicRunning to (name of processes) contains "Music"
+            end tell
+
+            if musicRunning then
+                tell application "Music"
+                    set out to ""
+                    repeat with p in user playlists
+                        set out to out & (persistent ID of p) & "|||" & (name of p) & linefeed
+                    end repeat
+                    return out
+                end tell
+            end if
+
+            return ""
+            "#,
+        ) else {
+            return Vec::new();
+        };
+
+        out.lines()
+            .filter_map(|line| {
+                let (id, name) = line.split_once("|||")?;
+                Some(Playlist {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn play_playlist(&self, id: &str) -> bool {
+        let Some(id) = persistent_id(id) else {
+            return false;
+        };
+        if !players().music {
+            return false;
+        }
+        osascript(&format!(
+            r#"
+            tell application "Music"
+                play (first user playlist whose persistent ID is "{id}")
+                return true
+            end tell
+            "#
+        ))
+        .is_some_and(|out| out == "true")
+    }
+
+    fn raise(&self, player: &str) -> bool {
+        // Only apps this backend reports (and `media.sources` allows) can be
+        // raised, so the name never reaches the script unchecked.
+        let Players { music, spotify, .. } = players();
+        let allowed = match player {
+            "Music" => music,
+            "Spotify" => spotify,
+            _ => false,
+        };
+        if !allowed {
+            return false;
+        }
+        osascript(&format!(
+            r#"
+            tell application "{player}" to activate
+            return true
+            "#
+        ))
+        .is_some_and(|out| out == "true")
+    }
+
+    fn volume(&self) -> Option<f64> {
+        let Players { music, spotify, .. } = players();
+        let out = osascript(&format!(
+            r#"
+            tell application "System Events"
+                set musicRunning to (name of processes) contains "Music"
+                set spotifyRunning to (name of processes) contains "Spotify"
+            end tell
+
+            if {music} and musicRunning then
+                tell application "Music" to return sound volume
+            end if
+
+            if {spotify} and spotifyRunning then
+                tell application "Spotify" to return sound volume
+            end if
+
+            return ""
+            "#
+        ))?;
+        out.parse::<f64>().ok().map(|v| (v / 100.0).clamp(0.0, 1.0))
+    }
+
+    fn set_volume(&self, volume: f64) -> bool {
+        let Players { music, spotify, .. } = players();
+        let level = (volume.clamp(0.0, 1.0) * 100.0).round() as i64;
+        osascript(&format!(
+            r#"
+            tell application "System Events"
+                set musicRunning to (name of processes) contains "Music"
+                set spotifyRunning to (name of processes) contains "Spotify"
+            end tell
+
+            if {music} and musicRunning then
+                tell application "Music" to set sound volume to {level}
+                return true
+            end if
+
+            if {spotify} and spotifyRunning then
+                tell application "Spotify" to set sound volume to {level}
+                return true
+            end if
+
+            return false
+            "#
+        ))
+        .is_some_and(|out| out == "true")
+    }
+}
+
+/// The sources `media.sources` lets through. Scripts skip the rest, so a
+/// filtered player neither shows nor takes commands and the next one does.
+struct Players {
+    music: bool,
+    spotify: bool,
+    /// The Now Playing center, which doesn't say which app is playing, so it
+    /// only passes without an allow list.
+    system: bool,
+}
+
+fn players() -> Players {
+    let filter = sources::filter();
+    Players {
+        music: filter.allows_player(&["Music"]),
+        spotify: filter.allows_player(&["Spotify"]),
+        system: filter.allows_player(&[]),
+    }
+}
+
+// Music persistent IDs are hex strings; rejecting anything else keeps the id
+// from being interpreted as script.
+fn persistent_id(id: &str) -> Option<&str> {
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit())).then_some(id)
+}
+
+// Runs a script and returns its trimmed stdout, or None if osascript failed.
+fn osascript(script: &str) -> Option<String> {
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
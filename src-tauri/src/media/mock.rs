@@ -0,0 +1,577 @@
+use super::{image_data_url, sources, MediaBackend, MediaInfo, QueueEntry, QUEUE_LIMIT};
+use crate::config::NotchConfig;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Env var pointing at a mock script; takes precedence over `media.mock.script`.
+pub const MOCK_ENV: &str = "NOTCH_MEDIA_MOCK";
+
+const PLAYER_NAME: &str = "Mock";
+
+// Like real players, "previous" restarts the track once it's this far in.
+const RESTART_THRESHOLD: f64 = 3.0;
+
+/// A scripted playlist: tracks play back in real time and `events` fire at fixed
+/// offsets from startup, as if someone were using the player.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MockScript {
+    pub tracks: Vec<MockTrack>,
+    #[serde(default)]
+    pub events: Vec<MockEvent>,
+    /// Start over from the first track after the last one ends.
+    #[serde(default = "default_true", rename = "loop")]
+    pub looping: bool,
+    /// Whether playback is running at startup.
+    #[serde(default = "default_true")]
+    pub playing: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MockTrack {
+    pub title: String,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub album: String,
+    pub duration: f64,
+    /// Image path (relative to the script) or an http/data URL.
+    #[serde(default)]
+    pub artwork: Option<String>,
+    /// Position the track starts from when it becomes current.
+    #[serde(default)]
+    pub start_at: f64,
+    #[serde(default)]
+    pub rating: Option<f64>,
+    #[serde(default)]
+    pub loved: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MockEvent {
+    /// Seconds after startup.
+    pub at: f64,
+    #[serde(flatten)]
+    pub action: MockAction,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum MockAction {
+    Play,
+    Pause,
+    Next,
+    Previous,
+    Seek {
+        position: f64,
+    },
+    Track {
+        index: usize,
+    },
+    /// Nothing playing at all, until the next `play` or `track`.
+    Stop,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Playback state of the mock player. Every method takes the current time so the
+/// whole thing can be driven by a fake clock.
+pub struct MockPlayer {
+    script: MockScript,
+    base_dir: PathBuf,
+    started: Instant,
+    index: usize,
+    playing: bool,
+    stopped: bool,
+    // Position at `since`; while playing, the track advances from there.
+    position: f64,
+    since: Instant,
+    next_event: usize,
+    volume: f64,
+}
+
+impl MockPlayer {
+    pub fn new(mut script: MockScript, base_dir: PathBuf, now: Instant) -> Result<Self, String> {
+        if script.tracks.is_empty() {
+            return Err("mock script has no tracks".into());
+        }
+        script.events.sort_by(|a, b| a.at.total_cmp(&b.at));
+        let position = script.tracks[0].start_at;
+        let playing = script.playing;
+        Ok(Self {
+            script,
+            base_dir,
+            started: now,
+            index: 0,
+            playing,
+            stopped: false,
+            position,
+            since: now,
+            next_event: 0,
+            volume: 1.0,
+        })
+    }
+
+    pub fn load(path: &Path, now: Instant) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("can't read mock script {}: {}", path.display(), e))?;
+        let script: MockScript = serde_json::from_str(&text)
+            .map_err(|e| format!("invalid mock script {}: {}", path.display(), e))?;
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self::new(script, base_dir, now)
+    }
+
+    fn track(&self) -> &MockTrack {
+        &self.script.tracks[self.index]
+    }
+
+    fn elapsed_at(&self, now: Instant) -> f64 {
+        if self.playing {
+            self.position + now.saturating_duration_since(self.since).as_secs_f64()
+        } else {
+            self.position
+        }
+    }
+
+    /// Applies due events and track ends up to `now`.
+    pub fn sync(&mut self, now: Instant) {
+        while let Some(event) = self.script.events.get(self.next_event) {
+            let at = self.started + std::time::Duration::from_secs_f64(event.at.max(0.0));
+            if at > now {
+                break;
+            }
+            let action = event.action.clone();
+            self.next_event += 1;
+            self.advance(at);
+            self.apply(action, at);
+        }
+        self.advance(now);
+    }
+
+    // Moves playback forward to `now`, rolling over into the following tracks.
+    fn advance(&mut self, now: Instant) {
+        let mut position = self.elapsed_at(now);
+        self.since = now;
+        while self.playing {
+            let duration = self.track().duration;
+            if duration <= 0.0 || position < duration {
+                break;
+            }
+            let overflow = position - duration;
+            if self.index + 1 < self.script.tracks.len() {
+                self.index += 1;
+            } else if self.script.looping {
+                self.index = 0;
+            } else {
+                // End of the script: rest paused at the end of the last track.
+                self.playing = false;
+                position = duration;
+                break;
+            }
+            position = self.track().start_at + overflow;
+        }
+        self.position = position;
+    }
+
+    fn apply(&mut self, action: MockAction, now: Instant) {
+        match action {
+            MockAction::Play => {
+                self.stopped = false;
+                self.playing = true;
+            }
+            MockAction::Pause => self.playing = false,
+            MockAction::Next => {
+                let next = (self.index + 1) % self.script.tracks.len();
+                self.jump(next);
+            }
+            MockAction::Previous => {
+                if self.elapsed_at(now) > RESTART_THRESHOLD {
+                    self.position = 0.0;
+                } else {
+                    let count = self.script.tracks.len();
+                    self.jump((self.index + count - 1) % count);
+                }
+            }
+            MockAction::Seek { position } => {
+                self.position = position.clamp(0.0, self.track().duration.max(0.0));
+            }
+            MockAction::Track { index } => {
+                if index < self.script.tracks.len() {
+                    self.stopped = false;
+                    self.jump(index);
+                }
+            }
+            MockAction::Stop => {
+                self.stopped = true;
+                self.playing = false;
+            }
+        }
+        self.since = now;
+    }
+
+    fn jump(&mut self, index: usize) {
+        self.index = index;
+        self.position = self.script.tracks[index].start_at;
+    }
+
+    /// Runs a user action, the same way a scripted event would.
+    pub fn control(&mut self, action: MockAction, now: Instant) {
+        self.sync(now);
+        self.apply(action, now);
+    }
+
+    pub fn now_playing(&mut self, now: Instant) -> Option<MediaInfo> {
+        self.sync(now);
+        if self.stopped {
+            return None;
+        }
+        let track = self.track();
+        Some(MediaInfo {
+            title: track.title.clone(),
+            artist: track.artist.clone(),
+            album: track.album.clone(),
+            artwork_url: None,
+            duration: track.duration,
+            elapsed: self.position,
+            is_playing: self.playing,
+            rating: track.rating.map(|r| r.clamp(0.0, 1.0)),
+            loved: track.loved,
+            can_rate: true,
+            player: Some(PLAYER_NAME.to_string()),
+        })
+    }
+
+    pub fn artwork(&mut self, now: Instant) -> Option<String> {
+        self.sync(now);
+        let artwork = self.track().artwork.as_deref()?;
+        if artwork.starts_with("http") || artwork.starts_with("data:image") {
+            return Some(artwork.to_string());
+        }
+        image_data_url(&self.base_dir.join(artwork))
+    }
+
+    pub fn queue(&mut self, now: Instant) -> Vec<QueueEntry> {
+        self.sync(now);
+        let count = self.script.tracks.len();
+        let upcoming = if self.script.looping {
+            count
+        } else {
+            count - self.index
+        };
+        (0..upcoming.min(QUEUE_LIMIT))
+            .map(|offset| {
+                let index = (self.index + offset) % count;
+                let track = &self.script.tracks[index];
+                QueueEntry {
+                    id: index.to_string(),
+                    title: track.title.clone(),
+                    artist: track.artist.clone(),
+                    duration: track.duration,
+                    is_current: offset == 0,
+                }
+            })
+            .collect()
+    }
+
+    pub fn set_rating(&mut self, rating: f64) {
+        let index = self.index;
+        self.script.tracks[index].rating = Some(rating.clamp(0.0, 1.0));
+    }
+
+    pub fn toggle_loved(&mut self) -> bool {
+        let index = self.index;
+        let track = &mut self.script.tracks[index];
+        track.loved = !track.loved;
+        track.loved
+    }
+}
+
+/// Serves a [`MockPlayer`] through the regular backend interface.
+pub struct MockBackend {
+    player: Mutex<MockPlayer>,
+}
+
+impl MockBackend {
+    /// Loads the script named by [`MOCK_ENV`] or `media.mock.script`, if any.
+    pub fn from_env_or_config() -> Option<Result<Self, String>> {
+        let script = std::env::var(MOCK_ENV)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| {
+                let script = &NotchConfig::get().media.mock.script.value;
+                (!script.is_empty()).then(|| script.clone())
+            })?;
+        Some(
+            MockPlayer::load(&resolve(&script), Instant::now()).map(|player| Self {
+                player: Mutex::new(player),
+            }),
+        )
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut MockPlayer, Instant) -> R) -> Option<R> {
+        self.player
+            .lock()
+            .ok()
+            .map(|mut player| f(&mut player, Instant::now()))
+    }
+
+    fn control(&self, action: MockAction) -> bool {
+        self.with(|player, now| player.control(action, now))
+            .is_some()
+    }
+}
+
+// Relative script paths are tried from the working directory, then from the
+// project root like the config file itself.
+fn resolve(script: &str) -> PathBuf {
+    let path = PathBuf::from(script);
+    if path.is_absolute() || path.exists() {
+        return path;
+    }
+    std::env::var("CARGO_MANIFEST_DIR")
+        .map(|dir| PathBuf::from(dir).join("..").join(&path))
+        .ok()
+        .filter(|p| p.exists())
+        .unwrap_or(path)
+}
+
+impl MediaBackend for MockBackend {
+    fn now_playing(&self) -> Option<MediaInfo> {
+        let info = self.with(|player, now| player.now_playing(now))??;
+        let filter = sources::filter();
+        (filter.allows_player(&[PLAYER_NAME]) && filter.allows_duration(info.duration))
+            .then_some(info)
+    }
+
+    fn artwork(&self) -> Option<String> {
+        self.with(|player, now| player.artwork(now))?
+    }
+
+    fn play_pause(&self) -> bool {
+        self.with(|player, now| {
+            player.sync(now);
+            let action = if player.playing {
+                MockAction::Pause
+            } else {
+                MockAction::Play
+            };
+            player.apply(action, now);
+        })
+        .is_some()
+    }
+
+    fn next_track(&self) -> bool {
+        self.control(MockAction::Next)
+    }
+
+    fn previous_track(&self) -> bool {
+        self.control(MockAction::Previous)
+    }
+
+    fn seek(&self, position: f64) -> bool {
+        position.is_finite() && self.control(MockAction::Seek { position })
+    }
+
+    fn set_rating(&self, rating: f64) -> bool {
+        self.with(|player, _| player.set_rating(rating)).is_some()
+    }
+
+    fn toggle_loved(&self) -> Option<bool> {
+        self.with(|player, _| player.toggle_loved())
+    }
+
+    fn queue(&self) -> Vec<QueueEntry> {
+        self.with(|player, now| player.queue(now))
+            .unwrap_or_default()
+    }
+
+    fn play_queue_entry(&self, id: &str) -> bool {
+        match id.parse() {
+            Ok(index) => self.control(MockAction::Track { index }),
+            Err(_) => false,
+        }
+    }
+
+    fn volume(&self) -> Option<f64> {
+        self.with(|player, _| player.volume)
+    }
+
+    fn set_volume(&self, volume: f64) -> bool {
+        self.with(|player, _| player.volume = volume.clamp(0.0, 1.0))
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // Two tracks: "One" (10s) and "Two" (20s, starting 1s in)
+    fn scripted(extra: &str) -> (MockPlayer, Instant) {
+        let script = format!(
+            r#"{{
+                "tracks": [
+                    {{ "title": "One", "duration": 10 }},
+                    {{ "title": "Two", "duration": 20, "start_at": 1 }}
+                ]{}
+            }}"#,
+            extra
+        );
+        let script: MockScript = serde_json::from_str(&script).unwrap();
+        let start = Instant::now();
+        (
+            MockPlayer::new(script, PathBuf::new(), start).unwrap(),
+            start,
+        )
+    }
+
+    fn at(start: Instant, secs: f64) -> Instant {
+        start + Duration::from_secs_f64(secs)
+    }
+
+    // Title, elapsed and whether it's playing at `secs`
+    fn state(player: &mut MockPlayer, start: Instant, secs: f64) -> (String, f64, bool) {
+        let info = player
+            .now_playing(at(start, secs))
+            .expect("nothing playing");
+        (info.title, info.elapsed, info.is_playing)
+    }
+
+    fn assert_state(actual: (String, f64, bool), title: &str, elapsed: f64, playing: bool) {
+        assert_eq!(actual.0, title);
+        assert!(
+            (actual.1 - elapsed).abs() < 1e-6,
+            "elapsed {} instead of {}",
+            actual.1,
+            elapsed
+        );
+        assert_eq!(actual.2, playing);
+    }
+
+    #[test]
+    fn position_advances_while_playing() {
+        let (mut player, start) = scripted("");
+        assert_state(state(&mut player, start, 0.0), "One", 0.0, true);
+        assert_state(state(&mut player, start, 4.5), "One", 4.5, true);
+    }
+
+    #[test]
+    fn tracks_roll_over_from_their_start() {
+        let (mut player, start) = scripted("");
+        assert_state(state(&mut player, start, 12.0), "Two", 3.0, true);
+        // Looping is on by default
+        assert_state(state(&mut player, start, 31.0), "One", 2.0, true);
+    }
+
+    #[test]
+    fn script_end_rests_paused_without_loop() {
+        let (mut player, start) = scripted(r#", "loop": false"#);
+        assert_state(state(&mut player, start, 60.0), "Two", 20.0, false);
+        assert_state(state(&mut player, start, 90.0), "Two", 20.0, false);
+    }
+
+    #[test]
+    fn scripted_pause_and_play_hold_the_position() {
+        let (mut player, start) = scripted(
+            r#", "events": [
+                { "at": 5, "action": "play" },
+                { "at": 3, "action": "pause" }
+            ]"#,
+        );
+        assert_state(state(&mut player, start, 4.0), "One", 3.0, false);
+        assert_state(state(&mut player, start, 7.0), "One", 5.0, true);
+    }
+
+    #[test]
+    fn events_fire_at_their_time_even_when_sampled_late() {
+        let (mut player, start) = scripted(
+            r#", "events": [
+                { "at": 2, "action": "next" },
+                { "at": 4, "action": "seek", "position": 15 }
+            ]"#,
+        );
+        assert_state(state(&mut player, start, 8.0), "Two", 19.0, true);
+    }
+
+    #[test]
+    fn starts_paused_when_asked() {
+        let (mut player, start) = scripted(r#", "playing": false"#);
+        assert_state(state(&mut player, start, 5.0), "One", 0.0, false);
+    }
+
+    #[test]
+    fn controls_play_pause_and_skip() {
+        let (mut player, start) = scripted("");
+        player.control(MockAction::Pause, at(start, 2.0));
+        assert_state(state(&mut player, start, 6.0), "One", 2.0, false);
+        player.control(MockAction::Play, at(start, 6.0));
+        assert_state(state(&mut player, start, 7.0), "One", 3.0, true);
+
+        player.control(MockAction::Next, at(start, 7.0));
+        assert_state(state(&mut player, start, 8.0), "Two", 2.0, true);
+        // Past the threshold "previous" restarts the track...
+        player.control(MockAction::Previous, at(start, 12.0));
+        assert_state(state(&mut player, start, 12.0), "Two", 0.0, true);
+        // ...and right after, goes back a track
+        player.control(MockAction::Previous, at(start, 13.0));
+        assert_state(state(&mut player, start, 13.0), "One", 0.0, true);
+    }
+
+    #[test]
+    fn seek_is_clamped_to_the_track() {
+        let (mut player, start) = scripted("");
+        player.control(MockAction::Pause, at(start, 1.0));
+        player.control(MockAction::Seek { position: 99.0 }, at(start, 1.0));
+        assert_state(state(&mut player, start, 2.0), "One", 10.0, false);
+        player.control(MockAction::Seek { position: -4.0 }, at(start, 2.0));
+        assert_state(state(&mut player, start, 3.0), "One", 0.0, false);
+    }
+
+    #[test]
+    fn stop_hides_the_player_until_played() {
+        let (mut player, start) = scripted(r#", "events": [{ "at": 1, "action": "stop" }]"#);
+        assert!(player.now_playing(at(start, 5.0)).is_none());
+        player.control(MockAction::Play, at(start, 5.0));
+        assert_state(state(&mut player, start, 6.0), "One", 2.0, true);
+    }
+
+    #[test]
+    fn queue_starts_at_the_current_track() {
+        let (mut player, start) = scripted(r#", "loop": false"#);
+        let queue = player.queue(at(start, 12.0));
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].title, "Two");
+        assert!(queue[0].is_current);
+
+        let (mut player, start) = scripted("");
+        let titles: Vec<String> = player
+            .queue(at(start, 12.0))
+            .into_iter()
+            .map(|entry| entry.title)
+            .collect();
+        assert_eq!(titles, ["Two", "One"]);
+    }
+
+    #[test]
+    fn rating_and_loved_stick_to_the_track() {
+        let (mut player, start) = scripted("");
+        player.set_rating(1.5);
+        assert!(player.toggle_loved());
+        let info = player.now_playing(at(start, 1.0)).unwrap();
+        assert_eq!(info.rating, Some(1.0));
+        assert!(info.loved);
+        let info = player.now_playing(at(start, 11.0)).unwrap();
+        assert_eq!(info.rating, None);
+        assert!(!info.loved);
+    }
+
+    #[test]
+    fn scripts_need_tracks() {
+        let script: MockScript = serde_json::from_str(r#"{ "tracks": [] }"#).unwrap();
+        assert!(MockPlayer::new(script, PathBuf::new(), Instant::now()).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(target_os = "macos")]
+mod apple_script;
+pub mod mock;
+#[cfg(target_os = "linux")]
+mod mpris;
+pub mod sleep_timer;
+pub mod sources;
+
+use crate::audio;
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Media info structure
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct MediaInfo {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub artwork_url: Option<String>,
+    pub duration: f64,
+    pub elapsed: f64,
+    pub is_playing: bool,
+    /// Track rating in `0.0..=1.0`, if the player reports one.
+    pub rating: Option<f64>,
+    pub loved: bool,
+    /// Whether the player takes [`set_rating`](MediaBackend::set_rating) and
+    /// [`toggle_loved`](MediaBackend::toggle_loved), so the UI can hide them.
+    pub can_rate: bool,
+    /// The player this came from (MPRIS bus name on Linux, app name on macOS),
+    /// used to raise it. `None` when the source can't be identified.
+    pub player: Option<String>,
+}
+
+impl MediaInfo {
+    /// Identifies the track the same way the frontend does (title, artist, duration),
+    /// so a change here means the player moved on to a different track.
+    pub fn track_id(&self) -> String {
+        format!("{}_{}_{}", self.title, self.artist, self.duration)
+    }
+}
+
+/// An upcoming (or the current) entry in the player's play queue.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct QueueEntry {
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+    pub duration: f64,
+    pub is_current: bool,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+}
+
+/// Upper bound on queue entries returned, enough for a mini queue without
+/// walking huge playlists.
+pub const QUEUE_LIMIT: usize = 20;
+
+/// A source of now-playing info and transport controls.
+pub trait MediaBackend: Send + Sync {
+    fn now_playing(&self) -> Option<MediaInfo>;
+    fn artwork(&self) -> Option<String>;
+    fn play_pause(&self) -> bool;
+    fn next_track(&self) -> bool;
+    fn previous_track(&self) -> bool;
+    fn seek(&self, position: f64) -> bool;
+
+    /// Sets the current track's rating in `0.0..=1.0`.
+    fn set_rating(&self, _rating: f64) -> bool {
+        false
+    }
+
+    /// Flips the current track's loved flag and returns the new state.
+    fn toggle_loved(&self) -> Option<bool> {
+        None
+    }
+
+    /// The current track followed by up to [`QUEUE_LIMIT`] - 1 upcoming ones.
+    fn queue(&self) -> Vec<QueueEntry> {
+        Vec::new()
+    }
+
+    fn play_queue_entry(&self, _id: &str) -> bool {
+        false
+    }
+
+    fn playlists(&self) -> Vec<Playlist> {
+        Vec::new()
+    }
+
+    fn play_playlist(&self, _id: &str) -> bool {
+        false
+    }
+
+    /// Brings `player` (a [`MediaInfo::player`] value) to the front.
+    fn raise(&self, _player: &str) -> bool {
+        false
+    }
+
+    /// Player volume in `0.0..=1.0`, or `None` if the player doesn't expose one.
+    fn volume(&self) -> Option<f64> {
+        None
+    }
+
+    fn set_volume(&self, _volume: f64) -> bool {
+        false
+    }
+}
+
+/// Reads a local image into a data URL the webview can display.
+pub fn image_data_url(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    let mime = match path.extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    };
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        BASE64_STANDARD.encode(bytes)
+    ))
+}
+
+static BACKEND: OnceLock<Box<dyn MediaBackend>> = OnceLock::new();
+
+pub fn backend() -> &'static dyn MediaBackend {
+    BACKEND
+        .get_or_init(|| match mock::MockBackend::from_env_or_config() {
+            Some(Ok(mock)) => {
+                eprintln!("Using mock media backend");
+                Box::new(mock)
+            }
+            Some(Err(e)) => {
+                eprintln!("Mock media backend disabled: {}", e);
+                platform_backend()
+            }
+            None => platform_backend(),
+        })
+        .as_ref()
+}
+
+fn platform_backend() -> Box<dyn MediaBackend> {
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(apple_script::AppleScriptBackend)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        match mpris::MprisBackend::connect() {
+            Ok(backend) => Box::new(backend),
+            Err(e) => {
+                eprintln!("MPRIS unavailable, media controls disabled: {}", e);
+                Box::new(NullBackend)
+            }
+        }
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Box::new(NullBackend)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+struct NullBackend;
+
+#[cfg(not(target_os = "macos"))]
+impl MediaBackend for NullBackend {
+    fn now_playing(&self) -> Option<MediaInfo> {
+        None
+    }
+
+    fn artwork(&self) -> Option<String> {
+        None
+    }
+
+    fn play_pause(&self) -> bool {
+        false
+    }
+
+    fn next_track(&self) -> bool {
+        false
+    }
+
+    fn previous_track(&self) -> bool {
+        false
+    }
+
+    fn seek(&self, _position: f64) -> bool {
+        false
+    }
+}
+
+#[tauri::command]
+pub fn get_current_media(
+    spectrum: tauri::State<audio::SpectrumState>,
+    app: tauri::AppHandle,
+) -> Option<MediaInfo> {
+    let media = backend().now_playing();
+    spectrum.set_playing(&app, media.as_ref().is_some_and(|m| m.is_playing));
+    media
+}
+
+// Get album artwork as base64 data URL
+#[tauri::command]
+pub fn get_media_artwork() -> Option<String> {
+    backend().artwork()
+}
+
+// Control media playback
+#[tauri::command]
+pub fn media_play_pause() -> bool {
+    backend().play_pause()
+}
+
+#[tauri::command]
+pub fn media_next_track() -> bool {
+    backend().next_track()
+}
+
+#[tauri::command]
+pub fn media_previous_track() -> bool {
+    backend().previous_track()
+}
+
+// Seek to a specific position in the track
+#[tauri::command]
+pub fn media_seek(position: f64) -> bool {
+    backend().seek(position)
+}
+
+#[tauri::command]
+pub fn media_set_rating(rating: f64) -> bool {
+    rating.is_finite() && backend().set_rating(rating)
+}
+
+// Returns the new loved state, or None if the player can't love tracks
+#[tauri::command]
+pub fn media_toggle_loved() -> Option<bool> {
+    backend().toggle_loved()
+}
+
+#[tauri::command]
+pub fn get_media_queue() -> Vec<QueueEntry> {
+    backend().queue()
+}
+
+#[tauri::command]
+pub fn get_media_playlists() -> Vec<Playlist> {
+    backend().playlists()
+}
+
+#[tauri::command]
+pub fn media_play_queue_entry(id: String) -> bool {
+    backend().play_queue_entry(&id)
+}
+
+#[tauri::command]
+pub fn media_play_playlist(id: String) -> bool {
+    backend().play_playlist(&id)
+}
+
+// Bring the player to the front. Defaults to the one currently reported as
+// now playing when the frontend doesn't pass one.
+#[tauri::command]
+pub fn media_raise_player(player: Option<String>) -> bool {
+    let backend = backend();
+    match player.or_else(|| backend.now_playing().and_then(|m| m.player)) {
+        Some(player) => backend.raise(&player),
+        None => false,
+    }
+}
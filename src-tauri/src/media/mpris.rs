@@ -0,0 +1,344 @@
+use super::{image_data_url, sources, MediaBackend, MediaInfo, Playlist, QueueEntry, QUEUE_LIMIT};
+use std::collections::HashMap;
+use std::path::Path;
+use zbus::blocking::{fdo::DBusProxy, proxy::Builder, Connection, Proxy};
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+const BUS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const ROOT_IFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_IFACE: &str = "org.mpris.MediaPlayer2.Player";
+const TRACKLIST_IFACE: &str = "org.mpris.MediaPlayer2.TrackList";
+const PLAYLISTS_IFACE: &str = "org.mpris.MediaPlayer2.Playlists";
+
+// Maximum number of playlists fetched from a player.
+const PLAYLIST_LIMIT: u32 = 100;
+
+const MICROS: f64 = 1_000_000.0;
+
+/// Talks to MPRIS players on the session bus. The active player is the first one
+/// that is playing, then the first one that is paused.
+pub struct MprisBackend {
+    conn: Connection,
+}
+
+impl MprisBackend {
+    pub fn connect() -> zbus::Result<Self> {
+        Ok(Self {
+            conn: Connection::session()?,
+        })
+    }
+
+    fn proxy(&self, bus_name: &str, interface: &'static str) -> zbus::Result<Proxy<'static>> {
+        // Position and Metadata change without reliable signals, so always read them fresh.
+        Builder::new(&self.conn)
+            .destination(bus_name.to_owned())?
+            .path(OBJECT_PATH)?
+            .interface(interface)?
+            .cache_properties(CacheProperties::No)
+            .build()
+    }
+
+    fn players(&self) -> Vec<String> {
+        let Ok(dbus) = DBusProxy::new(&self.conn) else {
+            return Vec::new();
+        };
+        dbus.list_names()
+            .map(|names| {
+                names
+                    .into_iter()
+                    .map(|name| name.to_string())
+                    .filter(|name| name.starts_with(BUS_PREFIX))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn active_player(&self) -> Option<Proxy<'static>> {
+        let mut paused = None;
+        for name in self.players() {
+            let Ok(proxy) = self.proxy(&name, PLAYER_IFACE) else {
+                continue;
+            };
+            match proxy.get_property::<String>("PlaybackStatus").as_deref() {
+                Ok("Playing") if self.accepts(&name, &proxy) => return Some(proxy),
+                Ok("Paused") if paused.is_none() && self.accepts(&name, &proxy) => {
+                    paused = Some(proxy)
+                }
+                _ => {}
+            }
+        }
+        paused
+    }
+
+    /// Applies `media.sources`, so filtered players never become the active one
+    /// and the next candidate shows instead.
+    fn accepts(&self, bus_name: &str, player: &Proxy) -> bool {
+        let filter = sources::filter();
+        if filter.has_player_rules() {
+            let identity = self
+                .proxy(bus_name, ROOT_IFACE)
+                .ok()
+                .and_then(|root| root.get_property::<String>("Identity").ok());
+            let mut names = vec![bus_name];
+            names.extend(identity.as_deref());
+            if !filter.allows_player(&names) {
+                return false;
+            }
+        }
+        if filter.has_duration_rule() {
+            let duration = Self::metadata(player)
+                .and_then(|m| meta_f64(&m, "mpris:length"))
+                .unwrap_or(0.0)
+                / MICROS;
+            if !filter.allows_duration(duration) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn metadata(player: &Proxy) -> Option<HashMap<String, OwnedValue>> {
+        player.get_property("Metadata").ok()
+    }
+
+    /// A proxy for another MPRIS interface on the active player's bus name.
+    fn active_interface(&self, interface: &'static str) -> Option<Proxy<'static>> {
+        let player = self.active_player()?;
+        self.proxy(player.destination().as_str(), interface).ok()
+    }
+
+    fn call(&self, method: &str) -> bool {
+        self.active_player()
+            .is_some_and(|player| player.call_method(method, &()).is_ok())
+    }
+}
+
+impl MediaBackend for MprisBackend {
+    fn now_playing(&self) -> Option<MediaInfo> {
+        let player = self.active_player()?;
+        let metadata = Self::metadata(&player)?;
+        let is_playing = player
+            .get_property::<String>("PlaybackStatus")
+            .is_ok_and(|status| status == "Playing");
+        let elapsed = player.get_property::<i64>("Position").unwrap_or(0) as f64 / MICROS;
+        let rating = meta_f64(&metadata, "xesam:userRating").map(|r| r.clamp(0.0, 1.0));
+
+        Some(MediaInfo {
+            title: meta_str(&metadata, "xesam:title").unwrap_or_default(),
+            artist: meta_strings(&metadata, "xesam:artist").join(", "),
+            album: meta_str(&metadata, "xesam:album").unwrap_or_default(),
+            artwork_url: meta_str(&metadata, "mpris:artUrl"),
+            duration: meta_f64(&metadata, "mpris:length").unwrap_or(0.0) / MICROS,
+            elapsed,
+            is_playing,
+            rating,
+            // MPRIS has no "loved" flag; a full-marks rating is the closest equivalent.
+            loved: rating.is_some_and(|r| r >= 1.0),
+            // xesam:userRating is read-only
+            can_rate: false,
+            player: Some(player.destination().to_string()),
+        })
+    }
+
+    fn artwork(&self) -> Option<String> {
+        let player = self.active_player()?;
+        let url = meta_str(&Self::metadata(&player)?, "mpris:artUrl")?;
+        if url.starts_with("http") || url.starts_with("data:image") {
+            return Some(url);
+        }
+
+        // Local covers (file://) can't be loaded by the webview, so inline them.
+        image_data_url(Path::new(url.strip_prefix("file://")?))
+    }
+
+    fn play_pause(&self) -> bool {
+        self.call("PlayPause")
+    }
+
+    fn next_track(&self) -> bool {
+        self.call("Next")
+    }
+
+    fn previous_track(&self) -> bool {
+        self.call("Previous")
+    }
+
+    fn seek(&self, position: f64) -> bool {
+        let Some(player) = self.active_player() else {
+            return false;
+        };
+        let Some(track_id) = Self::metadata(&player).and_then(|m| meta_path(&m, "mpris:trackid"))
+        else {
+            return false;
+        };
+        let Ok(track_id) = ObjectPath::try_from(track_id.as_str()) else {
+            return false;
+        };
+        let micros = (position.max(0.0) * MICROS) as i64;
+        player
+            .call_method("SetPosition", &(track_id, micros))
+            .is_ok()
+    }
+
+    // xesam:userRating is read-only in MPRIS, so set_rating/toggle_loved keep the
+    // trait defaults and report the capability as unsupported.
+
+    fn queue(&self) -> Vec<QueueEntry> {
+        let Some(player) = self.active_player() else {
+            return Vec::new();
+        };
+        let current = Self::metadata(&player).and_then(|m| meta_path(&m, "mpris:trackid"));
+        let Ok(tracklist) = self.proxy(player.destination().as_str(), TRACKLIST_IFACE) else {
+            return Vec::new();
+        };
+        let Ok(tracks) = tracklist.get_property::<Vec<OwnedObjectPath>>("Tracks") else {
+            return Vec::new();
+        };
+
+        let start = current
+            .as_deref()
+            .and_then(|id| tracks.iter().position(|t| t.as_str() == id))
+            .unwrap_or(0);
+        let window: Vec<OwnedObjectPath> =
+            tracks.into_iter().skip(start).take(QUEUE_LIMIT).collect();
+
+        tracklist
+            .call::<_, _, Vec<HashMap<String, OwnedValue>>>("GetTracksMetadata", &(window,))
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|metadata| {
+                        let id = meta_path(metadata, "mpris:trackid")?;
+                        Some(QueueEntry {
+                            is_current: current.as_deref() == Some(id.as_str()),
+                            id,
+                            title: meta_str(metadata, "xesam:title").unwrap_or_default(),
+                            artist: meta_strings(metadata, "xesam:artist").join(", "),
+                            duration: meta_f64(metadata, "mpris:length").unwrap_or(0.0) / MICROS,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn play_queue_entry(&self, id: &str) -> bool {
+        let Ok(track_id) = ObjectPath::try_from(id) else {
+            return false;
+        };
+        self.active_interface(TRACKLIST_IFACE)
+            .is_some_and(|tracklist| tracklist.call_method("GoTo", &(track_id,)).is_ok())
+    }
+
+    fn playlists(&self) -> Vec<Playlist> {
+        let Some(proxy) = self.active_interface(PLAYLISTS_IFACE) else {
+            return Vec::new();
+        };
+        // Not every player supports every ordering; fall back to alphabetical.
+        ["UserDefined", "Alphabetical"]
+            .iter()
+            .find_map(|order| {
+                proxy
+                    .call::<_, _, Vec<(OwnedObjectPath, String, String)>>(
+                        "GetPlaylists",
+                        &(0u32, PLAYLIST_LIMIT, *order, false),
+                    )
+                    .ok()
+            })
+            .map(|playlists| {
+                playlists
+                    .into_iter()
+                    .map(|(id, name, _icon)| Playlist {
+                        id: id.as_str().to_string(),
+                        name,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn play_playlist(&self, id: &str) -> bool {
+        let Ok(playlist_id) = ObjectPath::try_from(id) else {
+            return false;
+        };
+        self.active_interface(PLAYLISTS_IFACE).is_some_and(|playlists| {
+            playlists
+                .call_method("ActivatePlaylist", &(playlist_id,))
+                .is_ok()
+        })
+    }
+
+    fn raise(&self, player: &str) -> bool {
+        if !player.starts_with(BUS_PREFIX) {
+            return false;
+        }
+        self.proxy(player, ROOT_IFACE)
+            .is_ok_and(|root| root.call_method("Raise", &()).is_ok())
+    }
+
+    fn volume(&self) -> Option<f64> {
+        self.active_player()?.get_property::<f64>("Volume").ok()
+    }
+
+    fn set_volume(&self, volume: f64) -> bool {
+        self.active_player().is_some_and(|player| {
+            player
+                .set_property("Volume", volume.clamp(0.0, 1.0))
+                .is_ok()
+        })
+    }
+}
+
+fn meta_value<'a>(
+    metadata: &'a HashMap<String, OwnedValue>,
+    key: &str,
+) -> Option<&'a Value<'static>> {
+    let value: &Value = metadata.get(key)?;
+    // Some players wrap entries in an extra variant layer.
+    match value {
+        Value::Value(inner) => Some(inner),
+        other => Some(other),
+    }
+}
+
+fn meta_str(metadata: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    match meta_value(metadata, key)? {
+        Value::Str(s) => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+fn meta_path(metadata: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    match meta_value(metadata, key)? {
+        Value::ObjectPath(p) => Some(p.as_str().to_string()),
+        Value::Str(s) => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+fn meta_strings(metadata: &HashMap<String, OwnedValue>, key: &str) -> Vec<String> {
+    match meta_value(metadata, key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::Str(s) => Some(s.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::Str(s)) => vec![s.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+fn meta_f64(metadata: &HashMap<String, OwnedValue>, key: &str) -> Option<f64> {
+    match meta_value(metadata, key)? {
+        Value::F64(v) => Some(*v),
+        Value::I64(v) => Some(*v as f64),
+        Value::U64(v) => Some(*v as f64),
+        Value::I32(v) => Some(*v as f64),
+        Value::U32(v) => Some(*v as f64),
+        _ => None,
+    }
+}
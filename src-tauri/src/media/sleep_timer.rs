@@ -0,0 +1,480 @@
+use super::{backend, MediaBackend};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+/// Volume ramps down linearly over this window before playback pauses.
+pub const FADE_WINDOW: Duration = Duration::from_secs(30);
+
+const TICK: Duration = Duration::from_secs(1);
+const FADE_TICK: Duration = Duration::from_millis(250);
+
+/// Longest countdown `media_sleep_timer_start` accepts.
+pub const MAX_MINUTES: f64 = 24.0 * 60.0;
+
+// How long the player can report nothing before an end-of-track timer gives
+// up on it; shorter gaps are players restarting or a failed query.
+const MISSING_GRACE: Duration = Duration::from_secs(10);
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepMode {
+    Duration,
+    EndOfTrack,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SleepTimerStatus {
+    pub mode: SleepMode,
+    pub remaining_secs: f64,
+    pub fading: bool,
+}
+
+enum Target {
+    Deadline(Instant),
+    EndOfTrack(String),
+}
+
+impl Target {
+    fn mode(&self) -> SleepMode {
+        match self {
+            Target::Deadline(_) => SleepMode::Duration,
+            Target::EndOfTrack(_) => SleepMode::EndOfTrack,
+        }
+    }
+
+    /// Time left before playback should pause, or `None` while the player
+    /// reports nothing. A different track counts as the current one having ended.
+    fn remaining(&self, backend: &dyn MediaBackend) -> Option<Duration> {
+        match self {
+            Target::Deadline(at) => Some(at.saturating_duration_since(Instant::now())),
+            Target::EndOfTrack(track_id) => backend.now_playing().map(|media| {
+                if media.track_id() == *track_id {
+                    Duration::from_secs_f64((media.duration - media.elapsed).max(0.0))
+                } else {
+                    Duration::ZERO
+                }
+            }),
+        }
+    }
+}
+
+/// Pauses playback after a countdown or at the end of the current track.
+#[derive(Default)]
+pub struct SleepTimerState {
+    cancel: Mutex<Option<Arc<AtomicBool>>>,
+    status: Arc<Mutex<Option<SleepTimerStatus>>>,
+}
+
+impl SleepTimerState {
+    fn arm(&self, app: &AppHandle, target: Target) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        if let Ok(mut current) = self.cancel.lock() {
+            if let Some(previous) = current.replace(cancel.clone()) {
+                previous.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let app = app.clone();
+        let status = self.status.clone();
+        thread::spawn(move || {
+            run(backend(), target, &cancel, &status, |next| {
+                let _ = app.emit("media-sleep-timer", next);
+            })
+        });
+    }
+
+    fn cancel(&self, app: &AppHandle) {
+        if let Ok(mut current) = self.cancel.lock() {
+            if let Some(cancel) = current.take() {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        }
+        publish(app, &self.status, None);
+    }
+}
+
+/// Volume to apply `remaining` before the pause, given the volume when fading started.
+pub fn faded_volume(original: f64, remaining: Duration) -> f64 {
+    original * (remaining.as_secs_f64() / FADE_WINDOW.as_secs_f64()).clamp(0.0, 1.0)
+}
+
+fn run(
+    backend: &dyn MediaBackend,
+    target: Target,
+    cancel: &AtomicBool,
+    status: &Mutex<Option<SleepTimerStatus>>,
+    emit: impl Fn(&Option<SleepTimerStatus>),
+) {
+    // Volume before the fade began; None until fading starts or if unsupported.
+    let mut original_volume: Option<f64> = None;
+    let mut missing_since: Option<Instant> = None;
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let remaining = match target.remaining(backend) {
+            Some(remaining) => {
+                missing_since = None;
+                remaining
+            }
+            // The player went away for good
+            None if missing_since.get_or_insert_with(Instant::now).elapsed() >= MISSING_GRACE => {
+                Duration::ZERO
+            }
+            None => {
+                thread::sleep(TICK);
+                continue;
+            }
+        };
+        let fading = remaining <= FADE_WINDOW;
+        if fading {
+            if original_volume.is_none() {
+                original_volume = backend.volume();
+            }
+            if let Some(original) = original_volume {
+                backend.set_volume(faded_volume(original, remaining));
+            }
+        }
+
+        publish_running(
+            status,
+            cancel,
+            Some(SleepTimerStatus {
+                mode: target.mode(),
+                remaining_secs: remaining.as_secs_f64(),
+                fading,
+            }),
+            &emit,
+        );
+
+        let tick = if fading { FADE_TICK } else { TICK };
+        if remaining <= tick {
+            thread::sleep(remaining);
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            // media_play_pause toggles, so only fire it while something is playing
+            if backend.now_playing().is_some_and(|m| m.is_playing) {
+                backend.play_pause();
+            }
+            publish_running(status, cancel, None, &emit);
+            break;
+        }
+
+        thread::sleep(tick);
+    }
+
+    // Leave the player at its normal level for the next time it's started.
+    if let Some(original) = original_volume {
+        backend.set_volume(original);
+    }
+}
+
+// Emitted under the lock so listeners see changes in order
+fn publish(
+    app: &AppHandle,
+    status: &Mutex<Option<SleepTimerStatus>>,
+    next: Option<SleepTimerStatus>,
+) {
+    if let Ok(mut guard) = status.lock() {
+        *guard = next.clone();
+        let _ = app.emit("media-sleep-timer", next);
+    }
+}
+
+// Like `publish`, for the timer thread owning `cancel`. The flag is checked
+// under the lock, so once `SleepTimerState::cancel` (or a replacing timer) has
+// set it, a stale status can no longer overwrite the one published since.
+fn publish_running(
+    status: &Mutex<Option<SleepTimerStatus>>,
+    cancel: &AtomicBool,
+    next: Option<SleepTimerStatus>,
+    emit: impl Fn(&Option<SleepTimerStatus>),
+) {
+    if let Ok(mut guard) = status.lock() {
+        if !cancel.load(Ordering::Relaxed) {
+            emit(&next);
+            *guard = next;
+        }
+    }
+}
+
+#[tauri::command]
+pub fn media_sleep_timer_start(
+    minutes: f64,
+    app: AppHandle,
+    state: State<SleepTimerState>,
+) -> Result<(), String> {
+    if !minutes.is_finite() || minutes <= 0.0 || minutes > MAX_MINUTES {
+        return Err(format!(
+            "sleep timer needs between 0 and {} minutes",
+            MAX_MINUTES
+        ));
+    }
+    let deadline = Duration::try_from_secs_f64(minutes * 60.0)
+        .ok()
+        .and_then(|countdown| Instant::now().checked_add(countdown))
+        .ok_or_else(|| "sleep timer is too long".to_string())?;
+    state.arm(&app, Target::Deadline(deadline));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn media_sleep_timer_end_of_track(
+    app: AppHandle,
+    state: State<SleepTimerState>,
+) -> Result<(), String> {
+    let media = backend()
+        .now_playing()
+        .ok_or_else(|| "nothing is playing".to_string())?;
+    state.arm(&app, Target::EndOfTrack(media.track_id()));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn media_sleep_timer_cancel(app: AppHandle, state: State<SleepTimerState>) {
+    state.cancel(&app);
+}
+
+#[tauri::command]
+pub fn get_sleep_timer(state: State<SleepTimerState>) -> Option<SleepTimerStatus> {
+    state.status.lock().ok().and_then(|s| s.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::MediaInfo;
+
+    // A player whose track and volume the test sets, recording what the timer does
+    #[derive(Default)]
+    struct FakeBackend {
+        media: Mutex<Option<MediaInfo>>,
+        volumes: Mutex<Vec<f64>>,
+        play_pauses: Mutex<usize>,
+    }
+
+    impl FakeBackend {
+        fn playing(media: MediaInfo) -> Self {
+            let backend = Self::default();
+            backend.play(Some(media));
+            backend.volumes.lock().unwrap().push(0.8);
+            backend
+        }
+
+        fn play(&self, media: Option<MediaInfo>) {
+            *self.media.lock().unwrap() = media;
+        }
+
+        fn volumes(&self) -> Vec<f64> {
+            self.volumes.lock().unwrap().clone()
+        }
+
+        fn play_pauses(&self) -> usize {
+            *self.play_pauses.lock().unwrap()
+        }
+    }
+
+    impl MediaBackend for FakeBackend {
+        fn now_playing(&self) -> Option<MediaInfo> {
+            self.media.lock().unwrap().clone()
+        }
+
+        fn artwork(&self) -> Option<String> {
+            None
+        }
+
+        fn play_pause(&self) -> bool {
+            *self.play_pauses.lock().unwrap() += 1;
+            true
+        }
+
+        fn next_track(&self) -> bool {
+            false
+        }
+
+        fn previous_track(&self) -> bool {
+            false
+        }
+
+        fn seek(&self, _position: f64) -> bool {
+            false
+        }
+
+        fn volume(&self) -> Option<f64> {
+            self.volumes.lock().unwrap().last().copied()
+        }
+
+        fn set_volume(&self, volume: f64) -> bool {
+            self.volumes.lock().unwrap().push(volume);
+            true
+        }
+    }
+
+    fn track(title: &str, duration: f64, elapsed: f64) -> MediaInfo {
+        MediaInfo {
+            title: title.to_string(),
+            artist: "Artist".to_string(),
+            album: String::new(),
+            artwork_url: None,
+            duration,
+            elapsed,
+            is_playing: true,
+            rating: None,
+            loved: false,
+            can_rate: false,
+            player: None,
+        }
+    }
+
+    // Runs the timer to completion, returning every status it published
+    fn run_to_end(
+        backend: &FakeBackend,
+        target: Target,
+        cancel: &AtomicBool,
+    ) -> Vec<Option<SleepTimerStatus>> {
+        let published = Mutex::new(Vec::new());
+        run(backend, target, cancel, &Mutex::new(None), |next| {
+            published.lock().unwrap().push(next.clone())
+        });
+        published.into_inner().unwrap()
+    }
+
+    fn in_ms(ms: u64) -> Target {
+        Target::Deadline(Instant::now() + Duration::from_millis(ms))
+    }
+
+    #[test]
+    fn fade_ramps_down_over_the_window() {
+        assert_eq!(faded_volume(0.8, FADE_WINDOW * 2), 0.8);
+        assert_eq!(faded_volume(0.8, FADE_WINDOW), 0.8);
+        assert!((faded_volume(0.8, FADE_WINDOW / 2) - 0.4).abs() < 1e-9);
+        assert_eq!(faded_volume(0.8, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn deadline_pauses_after_fading_and_restores_the_volume() {
+        let backend = FakeBackend::playing(track("One", 100.0, 0.0));
+        let published = run_to_end(&backend, in_ms(300), &AtomicBool::new(false));
+
+        assert_eq!(backend.play_pauses(), 1);
+        // Well inside the fade window, so the volume starts near silent and
+        // goes back to 0.8 once paused
+        let volumes = backend.volumes();
+        assert_eq!(volumes.first(), Some(&0.8));
+        assert_eq!(volumes.last(), Some(&0.8));
+        let faded = &volumes[1..volumes.len() - 1];
+        assert!(!faded.is_empty());
+        assert!(
+            faded.iter().all(|&volume| volume < 0.8 * 0.02),
+            "{:?}",
+            faded
+        );
+        assert!(
+            faded.windows(2).all(|pair| pair[1] <= pair[0]),
+            "{:?}",
+            faded
+        );
+
+        let first = published.first().unwrap().as_ref().unwrap();
+        assert_eq!(first.mode, SleepMode::Duration);
+        assert!(first.fading);
+        assert!(published.last().unwrap().is_none());
+    }
+
+    #[test]
+    fn deadline_leaves_a_paused_player_alone() {
+        let mut paused = track("One", 100.0, 0.0);
+        paused.is_playing = false;
+        let backend = FakeBackend::playing(paused);
+        run_to_end(&backend, in_ms(50), &AtomicBool::new(false));
+        assert_eq!(backend.play_pauses(), 0);
+    }
+
+    #[test]
+    fn end_of_track_waits_for_the_track_to_run_out() {
+        let one = track("One", 100.0, 99.8);
+        let target = Target::EndOfTrack(one.track_id());
+        let backend = FakeBackend::playing(one);
+        let started = Instant::now();
+        let published = run_to_end(&backend, target, &AtomicBool::new(false));
+
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(backend.play_pauses(), 1);
+        let first = published.first().unwrap().as_ref().unwrap();
+        assert_eq!(first.mode, SleepMode::EndOfTrack);
+    }
+
+    #[test]
+    fn end_of_track_fires_when_the_track_changes() {
+        let one = track("One", 100.0, 10.0);
+        let target = Target::EndOfTrack(one.track_id());
+        let backend = FakeBackend::playing(one);
+        assert_eq!(target.remaining(&backend), Some(Duration::from_secs(90)));
+
+        backend.play(Some(track("Two", 100.0, 0.0)));
+        assert_eq!(target.remaining(&backend), Some(Duration::ZERO));
+        run_to_end(&backend, target, &AtomicBool::new(false));
+        assert_eq!(backend.play_pauses(), 1);
+    }
+
+    #[test]
+    fn end_of_track_outlasts_a_player_that_briefly_reports_nothing() {
+        let one = track("One", 100.0, 10.0);
+        let target = Target::EndOfTrack(one.track_id());
+        let backend = FakeBackend::playing(one.clone());
+        backend.play(None);
+        assert_eq!(target.remaining(&backend), None);
+
+        let cancel = AtomicBool::new(false);
+        thread::scope(|scope| {
+            scope.spawn(|| run_to_end(&backend, target, &cancel));
+            thread::sleep(Duration::from_millis(1500));
+            backend.play(Some(one));
+            thread::sleep(Duration::from_millis(100));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(backend.play_pauses(), 0);
+    }
+
+    #[test]
+    fn cancelled_timer_neither_fires_nor_publishes() {
+        let backend = FakeBackend::playing(track("One", 100.0, 0.0));
+        let cancel = AtomicBool::new(true);
+        assert!(run_to_end(&backend, in_ms(0), &cancel).is_empty());
+        assert_eq!(backend.play_pauses(), 0);
+    }
+
+    #[test]
+    fn cancel_wins_over_a_late_publish() {
+        // The timer thread was about to publish when it got cancelled and the
+        // status was cleared
+        let status = Mutex::new(None);
+        let cancel = AtomicBool::new(true);
+        let emitted = Mutex::new(0);
+        let late = SleepTimerStatus {
+            mode: SleepMode::Duration,
+            remaining_secs: 12.0,
+            fading: true,
+        };
+        publish_running(&status, &cancel, Some(late.clone()), |_| {
+            *emitted.lock().unwrap() += 1
+        });
+        assert!(status.lock().unwrap().is_none());
+        assert_eq!(*emitted.lock().unwrap(), 0);
+
+        cancel.store(false, Ordering::Relaxed);
+        publish_running(&status, &cancel, Some(late), |_| {
+            *emitted.lock().unwrap() += 1
+        });
+        assert!(status.lock().unwrap().is_some());
+        assert_eq!(*emitted.lock().unwrap(), 1);
+    }
+}
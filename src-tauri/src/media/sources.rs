@@ -0,0 +1,195 @@
+use crate::config::{MediaSourcesConfig, NotchConfig};
+use std::sync::OnceLock;
+
+/// Decides which players and tracks may reach the capsule, from `media.sources`.
+///
+/// A player passes if no deny pattern matches any of its names and, when allow
+/// patterns are set, at least one of them does. Patterns are case-insensitive and
+/// support `*` and `?` wildcards.
+pub struct SourceFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+    min_duration: f64,
+}
+
+impl SourceFilter {
+    pub fn from_config(config: &MediaSourcesConfig) -> Self {
+        let patterns = |list: &[String]| {
+            list.iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect()
+        };
+        Self {
+            allow: patterns(&config.allow.value),
+            deny: patterns(&config.deny.value),
+            min_duration: config.min_duration.value.max(0.0),
+        }
+    }
+
+    /// Whether any player pattern is configured, so backends can skip looking up
+    /// player identities when it wouldn't change the outcome.
+    pub fn has_player_rules(&self) -> bool {
+        !self.allow.is_empty() || !self.deny.is_empty()
+    }
+
+    pub fn has_duration_rule(&self) -> bool {
+        self.min_duration > 0.0
+    }
+
+    /// Shortest track length that passes, in seconds; 0 lets every track through.
+    pub fn min_duration(&self) -> f64 {
+        self.min_duration
+    }
+
+    /// Checks a player by all the names it's known under (identity, bus name, app name).
+    pub fn allows_player(&self, names: &[&str]) -> bool {
+        let names: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+        let matches = |pattern: &String| names.iter().any(|name| wildcard_match(pattern, name));
+
+        (self.allow.is_empty() || self.allow.iter().any(matches)) && !self.deny.iter().any(matches)
+    }
+
+    /// Tracks with an unknown length (0, e.g. live streams) always pass.
+    pub fn allows_duration(&self, duration: f64) -> bool {
+        duration <= 0.0 || duration >= self.min_duration
+    }
+}
+
+static FILTER: OnceLock<SourceFilter> = OnceLock::new();
+
+pub fn filter() -> &'static SourceFilter {
+    FILTER.get_or_init(|| SourceFilter::from_config(&NotchConfig::get().media.sources))
+}
+
+/// Glob-style match where `*` is any run of characters and `?` any single one.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it was tried against.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and retry.
+                Some((star, from)) => {
+                    p = star + 1;
+                    t = from + 1;
+                    backtrack = Some((star, from + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(allow: &[&str], deny: &[&str], min_duration: f64) -> SourceFilter {
+        let patterns = |list: &[&str]| list.iter().map(|p| p.to_lowercase()).collect();
+        SourceFilter {
+            allow: patterns(allow),
+            deny: patterns(deny),
+            min_duration,
+        }
+    }
+
+    #[test]
+    fn wildcard_literals_and_question_marks() {
+        assert!(wildcard_match("spotify", "spotify"));
+        assert!(!wildcard_match("spotify", "spotifyd"));
+        assert!(wildcard_match("spotif?", "spotify"));
+        assert!(!wildcard_match("spotif?", "spotif"));
+        assert!(wildcard_match("", ""));
+        assert!(!wildcard_match("", "music"));
+    }
+
+    #[test]
+    fn wildcard_stars() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match(
+            "org.mpris.mediaplayer2.*",
+            "org.mpris.mediaplayer2.vlc"
+        ));
+        assert!(wildcard_match(
+            "*firefox*",
+            "org.mpris.mediaplayer2.firefox.instance_1_42"
+        ));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("a*b*c", "aXbYbZ"));
+        // The first `*` has to give back what it swallowed
+        assert!(wildcard_match("*ab", "aab"));
+        assert!(wildcard_match("**", "x"));
+    }
+
+    #[test]
+    fn no_rules_allow_everything() {
+        let filter = filter(&[], &[], 0.0);
+        assert!(!filter.has_player_rules());
+        assert!(!filter.has_duration_rule());
+        assert!(filter.allows_player(&["Music"]));
+        assert!(filter.allows_player(&[]));
+        assert!(filter.allows_duration(3.0));
+    }
+
+    #[test]
+    fn allow_list_needs_one_matching_name() {
+        let filter = filter(&["spotify", "org.mpris.mediaplayer2.vlc"], &[], 0.0);
+        assert!(filter.allows_player(&["Spotify"]));
+        assert!(filter.allows_player(&["org.mpris.MediaPlayer2.vlc", "VLC media player"]));
+        assert!(!filter.allows_player(&["Music"]));
+        // Unidentified players can't match an allow list
+        assert!(!filter.allows_player(&[]));
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let filter = filter(&["*"], &["*chrom*"], 0.0);
+        assert!(filter.allows_player(&["Spotify"]));
+        assert!(!filter.allows_player(&["org.mpris.MediaPlayer2.chromium.instance2"]));
+        assert!(!filter.allows_player(&["Music", "Google Chrome"]));
+    }
+
+    #[test]
+    fn deny_list_lets_unidentified_players_through() {
+        let filter = filter(&[], &["music"], 0.0);
+        assert!(!filter.allows_player(&["Music"]));
+        assert!(filter.allows_player(&["Spotify"]));
+        assert!(filter.allows_player(&[]));
+    }
+
+    #[test]
+    fn min_duration_skips_short_tracks_but_not_streams() {
+        let filter = filter(&[], &[], 30.0);
+        assert!(filter.has_duration_rule());
+        assert!(!filter.allows_duration(12.0));
+        assert!(filter.allows_duration(30.0));
+        assert!(filter.allows_duration(0.0));
+    }
+
+    #[test]
+    fn config_patterns_are_trimmed_and_case_insensitive() {
+        let mut config = MediaSourcesConfig::default();
+        config.allow.value = vec!["  SPOTIFY ".into(), " ".into()];
+        config.min_duration.value = -5.0;
+        let filter = SourceFilter::from_config(&config);
+        assert_eq!(filter.allow, vec!["spotify"]);
+        assert!(filter.allows_player(&["Spotify"]));
+        assert!(!filter.has_duration_rule());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::controller::{self, NotchController};
+use crate::state::{NotchStage, NotchState, NotchStore};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::thread;
+use std::time::Duration;
+
+/// Dismisses a peek after `peek.dismiss_ms`. Starting a new peek, or leaving
+/// it for another stage, cancels the pending dismissal.
+#[derive(Default)]
+pub struct PeekTimer {
+    cancel: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl PeekTimer {
+    /// Runs `dismiss` after `after` unless cancelled or restarted first.
+    pub fn start(&self, after: Duration, dismiss: impl FnOnce() + Send + 'static) {
+        let cancel = Arc::new(AtomicBool::new(false));
+        if let Ok(mut current) = self.cancel.lock() {
+            if let Some(previous) = current.replace(cancel.clone()) {
+                previous.store(true, Ordering::Relaxed);
+            }
+        }
+        thread::spawn(move || {
+            thread::sleep(after);
+            if !cancel.load(Ordering::Relaxed) {
+                dismiss();
+            }
+        });
+    }
+
+    pub fn cancel(&self) {
+        if let Ok(mut current) = self.cancel.lock() {
+            if let Some(previous) = current.take() {
+                previous.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Returns a peeking capsule to compact, for when [`PeekTimer`] runs out.
+/// Does nothing once the capsule has moved to another stage. Otherwise returns
+/// what [`controller::transition`] does.
+pub fn dismiss(
+    controller: &dyn NotchController,
+    store: &NotchStore,
+    emit: impl Fn(&NotchState),
+) -> Result<bool, String> {
+    if store.get().stage != NotchStage::Peek {
+        return Ok(false);
+    }
+    controller::transition(controller, store, NotchStage::Compact, emit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::fake::{ControllerCall, RecordingController};
+    use crate::controller::NotchFrames;
+    use crate::state::NotchPhase;
+    use std::sync::mpsc::channel;
+    use std::time::Instant;
+
+    const DISMISS: Duration = Duration::from_millis(30);
+    // Long enough past DISMISS for a timer that was going to fire to have done so
+    const SETTLE: Duration = Duration::from_millis(200);
+    const PATIENCE: Duration = Duration::from_secs(5);
+
+    fn frames() -> NotchFrames {
+        NotchFrames {
+            closed_width: 200.0,
+            closed_height: 32.0,
+            expanded_width: 700.0,
+            expanded_height: 200.0,
+            peek_width: 300.0,
+            peek_height: 60.0,
+            corner_radius: 12.0,
+        }
+    }
+
+    // An attached controller, with the capsule at `stage`
+    fn at(stage: NotchStage) -> (Arc<RecordingController>, Arc<NotchStore>) {
+        let (controller, store) = (
+            Arc::new(RecordingController::default()),
+            Arc::new(NotchStore::default()),
+        );
+        controller.attach(frames()).unwrap();
+        controller::transition(&*controller, &store, stage, |_| {}).unwrap();
+        (controller, store)
+    }
+
+    #[test]
+    fn fires_after_its_delay() {
+        let timer = PeekTimer::default();
+        let (tx, rx) = channel();
+        let started = Instant::now();
+        timer.start(DISMISS, move || tx.send(()).unwrap());
+        rx.recv_timeout(PATIENCE).unwrap();
+        assert!(started.elapsed() >= DISMISS);
+    }
+
+    #[test]
+    fn restarting_replaces_the_pending_dismissal() {
+        let timer = PeekTimer::default();
+        let (tx, rx) = channel();
+        let first = tx.clone();
+        timer.start(DISMISS, move || first.send("first").unwrap());
+        timer.start(DISMISS * 2, move || tx.send("second").unwrap());
+        assert_eq!(rx.recv_timeout(PATIENCE), Ok("second"));
+        thread::sleep(SETTLE);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn cancelled_timer_never_fires() {
+        let timer = PeekTimer::default();
+        let (tx, rx) = channel();
+        timer.start(DISMISS, move || tx.send(()).unwrap());
+        timer.cancel();
+        thread::sleep(SETTLE);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn dismissal_returns_a_peek_to_compact() {
+        let (controller, store) = at(NotchStage::Peek);
+        let (tx, rx) = channel();
+        let (dismissing, dismissed) = (controller.clone(), store.clone());
+        PeekTimer::default().start(DISMISS, move || {
+            tx.send(dismiss(&*dismissing, &dismissed, |_| {})).unwrap()
+        });
+
+        assert_eq!(rx.recv_timeout(PATIENCE), Ok(Ok(false)));
+        assert_eq!(store.get().stage, NotchStage::Compact);
+        assert_eq!(
+            controller.calls().last(),
+            Some(&ControllerCall::Show(NotchStage::Compact))
+        );
+    }
+
+    #[test]
+    fn dismissal_leaves_a_capsule_that_moved_on() {
+        let (controller, store) = at(NotchStage::Peek);
+        let (tx, rx) = channel();
+        let (dismissing, dismissed) = (controller.clone(), store.clone());
+        PeekTimer::default().start(DISMISS, move || {
+            tx.send(dismiss(&*dismissing, &dismissed, |_| {})).unwrap()
+        });
+        controller::transition(&*controller, &store, NotchStage::Expanded, |_| {}).unwrap();
+
+        assert_eq!(rx.recv_timeout(PATIENCE), Ok(Ok(false)));
+        assert_eq!(store.get().stage, NotchStage::Expanded);
+        assert_eq!(
+            controller.calls(),
+            [
+                ControllerCall::Attach(frames()),
+                ControllerCall::Show(NotchStage::Peek),
+                ControllerCall::Show(NotchStage::Expanded),
+            ]
+        );
+    }
+
+    #[test]
+    fn expanded_capsule_shrinks_to_a_peek() {
+        let (controller, store) = at(NotchStage::Expanded);
+        controller::transition(&*controller, &store, NotchStage::Peek, |_| {}).unwrap();
+        let state = store.get();
+        assert_eq!(
+            (state.phase, state.stage),
+            (NotchPhase::Collapsing, NotchStage::Peek)
+        );
+        assert_eq!(
+            controller.calls().last(),
+            Some(&ControllerCall::Show(NotchStage::Peek))
+        );
+        assert_eq!(dismiss(&*controller, &store, |_| {}), Ok(false));
+        assert_eq!(store.get().stage, NotchStage::Compact);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::config::NotchConfig;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const PIN_FILE: &str = "pin.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct PinFile {
+    pinned: bool,
+}
+
+fn path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(PIN_FILE))
+}
+
+/// The pinned state saved by the last run, if `pin.persist` is on.
+pub fn load(app: &AppHandle) -> bool {
+    if !NotchConfig::get().pin.persist.value {
+        return false;
+    }
+    path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str::<PinFile>(&text).ok())
+        .is_some_and(|file| file.pinned)
+}
+
+/// Saves the pinned state for the next run, if `pin.persist` is on.
+pub fn save(app: &AppHandle, pinned: bool) {
+    if !NotchConfig::get().pin.persist.value {
+        return;
+    }
+    let Some(path) = path(app) else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| {
+            let text = serde_json::to_string(&PinFile { pinned }).unwrap_or_default();
+            std::fs::write(&path, text)
+        });
+    if let Err(e) = result {
+        eprintln!("Failed to save pinned state to {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,326 @@
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Shoulder radius as a fraction of the corner radius when compact and when
+/// expanded; the shoulders flare out as the capsule grows.
+const CLOSED_SHOULDER: f64 = 0.5;
+const OPEN_SHOULDER: f64 = 0.8;
+
+/// Points per curve when flattening the outline into a polygon.
+const CURVE_SEGMENTS: usize = 8;
+
+/// Control point distance for a quarter circle drawn as a cubic bezier.
+const KAPPA: f64 = 0.552_284_75;
+
+/// The capsule's outline: a body hanging from the top edge with rounded bottom
+/// corners, and concave shoulders at the top that blend it into the menu bar.
+/// In points, y-down from the top-left of the bounding box, whose top edge
+/// spans both shoulders.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Silhouette {
+    pub width: f64,
+    pub height: f64,
+    pub corner_radius: f64,
+    pub shoulder_radius: f64,
+}
+
+/// A cubic bezier from the previous point through two controls to `to`.
+#[derive(Clone, Copy, Debug)]
+struct Curve {
+    c1: (f64, f64),
+    c2: (f64, f64),
+    to: (f64, f64),
+}
+
+enum Segment {
+    Line((f64, f64)),
+    Curve(Curve),
+}
+
+impl Silhouette {
+    /// The outline at `progress` between compact (0) and expanded (1). Radii
+    /// shrink together if the box is too small for them.
+    pub fn new(width: f64, height: f64, corner_radius: f64, progress: f64) -> Self {
+        let (width, height) = (width.max(0.0), height.max(0.0));
+        let progress = progress.clamp(0.0, 1.0);
+        let corner = corner_radius.max(0.0);
+        let shoulder = corner * (CLOSED_SHOULDER + (OPEN_SHOULDER - CLOSED_SHOULDER) * progress);
+        // Both radii stack vertically, and twice across
+        let needed = shoulder + corner;
+        let fit = if needed > 0.0 {
+            (height / needed).min(width / (2.0 * needed)).min(1.0)
+        } else {
+            1.0
+        };
+        Self {
+            width,
+            height,
+            corner_radius: corner * fit,
+            shoulder_radius: shoulder * fit,
+        }
+    }
+
+    // Clockwise from the top-left corner; the top edge closes the outline
+    fn segments(&self) -> [Segment; 7] {
+        let (w, h) = (self.width, self.height);
+        let (s, r) = (self.shoulder_radius, self.corner_radius);
+        let (ks, kr) = (KAPPA * s, KAPPA * r);
+        [
+            Segment::Curve(Curve {
+                c1: (ks, 0.0),
+                c2: (s, s - ks),
+                to: (s, s),
+            }),
+            Segment::Line((s, h - r)),
+            Segment::Curve(Curve {
+                c1: (s, h - r + kr),
+                c2: (s + r - kr, h),
+                to: (s + r, h),
+            }),
+            Segment::Line((w - s - r, h)),
+            Segment::Curve(Curve {
+                c1: (w - s - r + kr, h),
+                c2: (w - s, h - r + kr),
+                to: (w - s, h - r),
+            }),
+            Segment::Line((w - s, s)),
+            Segment::Curve(Curve {
+                c1: (w - s, s - ks),
+                c2: (w - ks, 0.0),
+                to: (w, 0.0),
+            }),
+        ]
+    }
+
+    /// The outline as an SVG path, for a `viewBox` of `0 0 width height`.
+    pub fn svg_path(&self) -> String {
+        let mut path = String::from("M0 0");
+        for segment in self.segments() {
+            let _ = match segment {
+                Segment::Line((x, y)) => write!(path, "L{} {}", num(x), num(y)),
+                Segment::Curve(Curve { c1, c2, to }) => write!(
+                    path,
+                    "C{} {} {} {} {} {}",
+                    num(c1.0),
+                    num(c1.1),
+                    num(c2.0),
+                    num(c2.1),
+                    num(to.0),
+                    num(to.1)
+                ),
+            };
+        }
+        path.push('Z');
+        path
+    }
+
+    /// The outline flattened into a closed polygon, without repeating the
+    /// first point.
+    pub fn polygon(&self) -> Vec<(f64, f64)> {
+        let mut points = vec![(0.0, 0.0)];
+        for segment in self.segments() {
+            let from = *points.last().unwrap_or(&(0.0, 0.0));
+            match segment {
+                Segment::Line(to) => points.push(to),
+                Segment::Curve(curve) => points.extend(
+                    (1..=CURVE_SEGMENTS).map(|i| curve.at(from, i as f64 / CURVE_SEGMENTS as f64)),
+                ),
+            }
+        }
+        points
+    }
+
+    /// Whether `(x, y)` is inside the outline, edges included.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        if x < 0.0 || y < 0.0 || x > self.width || y > self.height {
+            return false;
+        }
+        polygon_contains(&self.polygon(), x, y)
+    }
+}
+
+impl Curve {
+    fn at(&self, from: (f64, f64), t: f64) -> (f64, f64) {
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        (
+            a * from.0 + b * self.c1.0 + c * self.c2.0 + d * self.to.0,
+            a * from.1 + b * self.c1.1 + c * self.c2.1 + d * self.to.1,
+        )
+    }
+}
+
+// Even-odd test of `(x, y)` against a closed polygon. Points on the top edge
+// count as inside, so a pointer pinned to the top of the screen does
+fn polygon_contains(polygon: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut previous = match polygon.last() {
+        Some(&point) => point,
+        None => return false,
+    };
+    for &point in polygon {
+        let ((x1, y1), (x2, y2)) = (previous, point);
+        if y1 == y2 && y == y1 && x >= x1.min(x2) && x <= x1.max(x2) {
+            return true;
+        }
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+        previous = point;
+    }
+    inside
+}
+
+// Two decimals at most, without trailing zeros
+fn num(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" | "" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct NotchSilhouette {
+    pub path: String,
+    pub polygon: Vec<(f64, f64)>,
+}
+
+/// The capsule outline for masks, sized in points; see [`Silhouette::new`].
+#[tauri::command]
+pub fn get_notch_silhouette(width: f64, height: f64, progress: f64) -> NotchSilhouette {
+    let corner = crate::config::NotchConfig::get()
+        .dimensions
+        .corner_radius
+        .value;
+    let silhouette = Silhouette::new(width, height, corner, progress);
+    NotchSilhouette {
+        path: silhouette.svg_path(),
+        polygon: silhouette.polygon(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(points: &[(f64, f64)]) -> String {
+        points
+            .iter()
+            .map(|&(x, y)| format!("{} {}", num(x), num(y)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn radii_shrink_to_fit_the_box() {
+        let cases = [
+            // Shoulders grow from half the corner radius to 0.8 of it
+            ("compact", (200.0, 40.0, 10.0, 0.0), (10.0, 5.0)),
+            ("halfway", (200.0, 40.0, 10.0, 0.5), (10.0, 6.5)),
+            ("expanded", (200.0, 40.0, 10.0, 1.0), (10.0, 8.0)),
+            ("progress clamped", (200.0, 40.0, 10.0, 3.0), (10.0, 8.0)),
+            // 30 needed vertically in a 12 high box
+            ("too short", (200.0, 12.0, 20.0, 0.0), (8.0, 4.0)),
+            // 60 needed across a 30 wide box
+            ("too narrow", (30.0, 100.0, 20.0, 0.0), (10.0, 5.0)),
+            ("negative radius", (200.0, 40.0, -5.0, 0.0), (0.0, 0.0)),
+            ("empty box", (0.0, 0.0, 10.0, 1.0), (0.0, 0.0)),
+        ];
+        for (name, (width, height, corner, progress), (r, s)) in cases {
+            let silhouette = Silhouette::new(width, height, corner, progress);
+            assert!(
+                (silhouette.corner_radius - r).abs() < 1e-9,
+                "{name}: corner {}",
+                silhouette.corner_radius
+            );
+            assert!(
+                (silhouette.shoulder_radius - s).abs() < 1e-9,
+                "{name}: shoulder {}",
+                silhouette.shoulder_radius
+            );
+        }
+    }
+
+    #[test]
+    fn svg_paths() {
+        let cases = [
+            (
+                "compact",
+                (200.0, 40.0, 10.0, 0.0),
+                "M0 0C2.76 0 5 2.24 5 5L5 30C5 35.52 9.48 40 15 40L185 40\
+                 C190.52 40 195 35.52 195 30L195 5C195 2.24 197.24 0 200 0Z",
+            ),
+            (
+                "expanded",
+                (300.0, 100.0, 20.0, 1.0),
+                "M0 0C8.84 0 16 7.16 16 16L16 80C16 91.05 24.95 100 36 100L264 100\
+                 C275.05 100 284 91.05 284 80L284 16C284 7.16 291.16 0 300 0Z",
+            ),
+            (
+                "clamped",
+                (200.0, 12.0, 20.0, 0.0),
+                "M0 0C2.21 0 4 1.79 4 4L4 4C4 8.42 7.58 12 12 12L188 12\
+                 C192.42 12 196 8.42 196 4L196 4C196 1.79 197.79 0 200 0Z",
+            ),
+            (
+                "square",
+                (50.0, 20.0, 0.0, 1.0),
+                "M0 0C0 0 0 0 0 0L0 20C0 20 0 20 0 20L50 20C50 20 50 20 50 20L50 0C50 0 50 0 50 0Z",
+            ),
+        ];
+        for (name, (width, height, corner, progress), expected) in cases {
+            let path = Silhouette::new(width, height, corner, progress).svg_path();
+            assert_eq!(path, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn polygons() {
+        let compact = Silhouette::new(200.0, 40.0, 10.0, 0.0).polygon();
+        // The start, then 8 points per curve and one per line
+        assert_eq!(compact.len(), 1 + 4 * CURVE_SEGMENTS + 3);
+        assert_eq!(
+            outline(&compact),
+            "0 0, 1.01 0.1, 1.95 0.39, 2.8 0.85, 3.54 1.46, 4.15 2.2, 4.61 3.05, 4.9 3.99, 5 5, \
+             5 30, 5.2 32.02, 5.79 33.89, 6.71 35.59, 7.93 37.07, 9.41 38.29, 11.11 39.21, \
+             12.98 39.8, 15 40, 185 40, 187.02 39.8, 188.89 39.21, 190.59 38.29, 192.07 37.07, \
+             193.29 35.59, 194.21 33.89, 194.8 32.02, 195 30, 195 5, 195.1 3.99, 195.39 3.05, \
+             195.85 2.2, 196.46 1.46, 197.2 0.85, 198.05 0.39, 198.99 0.1, 200 0"
+        );
+
+        let clamped = Silhouette::new(40.0, 10.0, 20.0, 0.0).polygon();
+        assert!(clamped
+            .iter()
+            .all(|&(x, y)| (0.0..=40.0).contains(&x) && (0.0..=10.0).contains(&y)));
+        assert_eq!(clamped.last(), Some(&(40.0, 0.0)));
+    }
+
+    #[test]
+    fn contains_follows_shoulders_and_corners() {
+        // Shoulders of radius 5 centered on (0, 5) and (200, 5); bottom corners
+        // of radius 10 centered on (15, 30) and (185, 30)
+        let silhouette = Silhouette::new(200.0, 40.0, 10.0, 0.0);
+        let cases = [
+            ("top edge", 1.0, 0.0, true),
+            ("middle", 100.0, 20.0, true),
+            ("bottom edge", 100.0, 40.0, true),
+            ("below", 100.0, 40.5, false),
+            ("left of the box", -1.0, 0.0, false),
+            ("under the left shoulder", 1.0, 4.0, false),
+            ("on the left shoulder", 4.0, 1.0, true),
+            ("under the right shoulder", 199.0, 4.0, false),
+            ("on the right shoulder", 196.0, 1.0, true),
+            ("beside the body", 4.5, 20.0, false),
+            ("inside the body", 5.5, 20.0, true),
+            ("cut by the left corner", 6.0, 39.0, false),
+            ("inside the left corner", 14.0, 38.0, true),
+            ("cut by the right corner", 194.0, 39.0, false),
+            ("inside the right corner", 186.0, 38.0, true),
+        ];
+        for (name, x, y, inside) in cases {
+            assert_eq!(silhouette.contains(x, y), inside, "{name}");
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Monitor};
+
+/// Emitted with the whole [`NotchState`] whenever any part of it changes.
+pub const STATE_CHANGED_EVENT: &str = "notch-state-changed";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotchPhase {
+    #[default]
+    Collapsed,
+    Expanding,
+    Expanded,
+    Collapsing,
+}
+
+impl NotchPhase {
+    /// Whether the window is at its expanded size, so hover uses the expanded
+    /// zones. It only gets there once expanding finishes and stays until
+    /// collapsing does.
+    pub fn is_open(self) -> bool {
+        matches!(self, NotchPhase::Expanded | NotchPhase::Collapsing)
+    }
+
+    /// The phase after starting to expand or collapse from this one. Starting
+    /// towards where the notch already is, or is already heading, changes
+    /// nothing.
+    pub fn toward(self, expanded: bool) -> Self {
+        match (self, expanded) {
+            (NotchPhase::Expanding | NotchPhase::Expanded, true) => self,
+            (NotchPhase::Collapsing | NotchPhase::Collapsed, false) => self,
+            (_, true) => NotchPhase::Expanding,
+            (_, false) => NotchPhase::Collapsing,
+        }
+    }
+
+    /// Not in the middle of an animation.
+    pub fn is_settled(self) -> bool {
+        matches!(self, NotchPhase::Collapsed | NotchPhase::Expanded)
+    }
+
+    pub fn settled(expanded: bool) -> Self {
+        if expanded {
+            NotchPhase::Expanded
+        } else {
+            NotchPhase::Collapsed
+        }
+    }
+}
+
+/// How big the capsule is: compact around the notch, peeking with transient
+/// info like a track change, or expanded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotchStage {
+    #[default]
+    Compact,
+    Peek,
+    Expanded,
+}
+
+impl NotchStage {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => NotchStage::Peek,
+            2 => NotchStage::Expanded,
+            _ => NotchStage::Compact,
+        }
+    }
+}
+
+/// A [`NotchStage`], and the [`NotchView`] that sizes the expanded stage, that
+/// can be read without locking.
+#[derive(Debug, Default)]
+pub struct SharedStage {
+    stage: AtomicU8,
+    view: AtomicU8,
+}
+
+impl SharedStage {
+    pub fn load(&self) -> (NotchStage, NotchView) {
+        (
+            NotchStage::from_u8(self.stage.load(Ordering::Relaxed)),
+            NotchView::from_u8(self.view.load(Ordering::Relaxed)),
+        )
+    }
+
+    fn store(&self, stage: NotchStage, view: NotchView) {
+        self.stage.store(stage as u8, Ordering::Relaxed);
+        self.view.store(view as u8, Ordering::Relaxed);
+    }
+}
+
+/// The tab shown in the expanded notch (`TabId` on the frontend).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotchView {
+    #[default]
+    App,
+    Archive,
+    Settings,
+}
+
+impl NotchView {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => NotchView::Archive,
+            2 => NotchView::Settings,
+            _ => NotchView::App,
+        }
+    }
+}
+
+/// The monitor hosting the capsule window, in physical pixels.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NotchDisplay {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl From<&Monitor> for NotchDisplay {
+    fn from(monitor: &Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct NotchState {
+    pub phase: NotchPhase,
+    /// The stage the capsule is at or heading to. `phase` still tracks
+    /// expanding and collapsing; compact and peek are both collapsed.
+    pub stage: NotchStage,
+    pub pinned: bool,
+    /// Whether the capsule window has keyboard focus.
+    pub focused: bool,
+    pub active_view: NotchView,
+    pub display: Option<NotchDisplay>,
+}
+
+impl NotchState {
+    /// Moves to `phase`, keeping `stage` in step: expanding means heading to
+    /// expanded, and collapsing from expanded lands on compact. Settling
+    /// collapsed unpins, since a pinned notch stays expanded.
+    pub fn set_phase(&mut self, phase: NotchPhase) {
+        self.phase = phase;
+        if matches!(phase, NotchPhase::Expanding | NotchPhase::Expanded) {
+            self.stage = NotchStage::Expanded;
+        } else if self.stage == NotchStage::Expanded {
+            self.stage = NotchStage::Compact;
+        }
+        if phase == NotchPhase::Collapsed {
+            self.pinned = false;
+        }
+    }
+}
+
+/// Emits a changed state as [`STATE_CHANGED_EVENT`], for [`NotchStore::apply`].
+pub fn emit_to(app: &AppHandle) -> impl Fn(&NotchState) + '_ {
+    move |state| {
+        let _ = app.emit(STATE_CHANGED_EVENT, state);
+    }
+}
+
+/// The one copy of [`NotchState`]. Commands and window events change it through
+/// [`update`](Self::update); everyone else reads it or listens for
+/// [`STATE_CHANGED_EVENT`].
+#[derive(Default)]
+pub struct NotchStore {
+    state: Mutex<NotchState>,
+    // Read on every hover poll, so kept outside the lock
+    zones: Arc<SharedStage>,
+    pinned: Arc<AtomicBool>,
+}
+
+impl NotchStore {
+    pub fn get(&self) -> NotchState {
+        self.state
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+
+    /// The stage whose hover zones apply, for the hover pointer sources:
+    /// expanded while [`NotchPhase::is_open`], otherwise compact or peek. The
+    /// zones stay put while expanding so the pointer can't drop out of them.
+    /// The expanded zones follow the active view's size.
+    pub fn zone_stage(&self) -> Arc<SharedStage> {
+        self.zones.clone()
+    }
+
+    /// [`NotchState::pinned`], for the hover machine to read on every sample.
+    /// Only [`update`](Self::update) writes it.
+    pub fn pinned(&self) -> Arc<AtomicBool> {
+        self.pinned.clone()
+    }
+
+    /// Applies `change` and emits [`STATE_CHANGED_EVENT`] if it changed
+    /// anything. Returns whether it did.
+    pub fn update(&self, app: &AppHandle, change: impl FnOnce(&mut NotchState)) -> bool {
+        self.apply(change, emit_to(app))
+    }
+
+    /// [`update`](Self::update), handing the changed state to `emit` instead
+    /// of emitting it.
+    pub fn apply(
+        &self,
+        change: impl FnOnce(&mut NotchState),
+        emit: impl FnOnce(&NotchState),
+    ) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let before = state.clone();
+        change(&mut state);
+        if *state == before {
+            return false;
+        }
+        let (zone_stage, _) = self.zones.load();
+        let zone_stage = match state.phase {
+            phase if phase.is_open() => NotchStage::Expanded,
+            NotchPhase::Expanding => zone_stage,
+            _ if state.stage == NotchStage::Peek => NotchStage::Peek,
+            _ => NotchStage::Compact,
+        };
+        self.zones.store(zone_stage, state.active_view);
+        self.pinned.store(state.pinned, Ordering::Relaxed);
+        if state.phase != before.phase {
+            eprintln!("Notch phase: {:?} -> {:?}", before.phase, state.phase);
+        }
+        if state.stage != before.stage {
+            eprintln!("Notch stage: {:?} -> {:?}", before.stage, state.stage);
+        }
+        // Emitted under the lock so listeners see changes in order
+        emit(&state);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NotchPhase::*;
+
+    #[test]
+    fn toward_starts_a_transition_unless_already_there() {
+        let cases = [
+            (Collapsed, true, Expanding),
+            (Collapsing, true, Expanding),
+            (Expanding, true, Expanding),
+            (Expanded, true, Expanded),
+            (Expanded, false, Collapsing),
+            (Expanding, false, Collapsing),
+            (Collapsing, false, Collapsing),
+            (Collapsed, false, Collapsed),
+        ];
+        for (from, expanded, to) in cases {
+            assert_eq!(from.toward(expanded), to, "{:?} toward {}", from, expanded);
+        }
+    }
+
+    #[test]
+    fn set_phase_keeps_the_stage_in_step() {
+        let mut state = NotchState {
+            stage: NotchStage::Peek,
+            ..NotchState::default()
+        };
+        state.set_phase(Expanding);
+        assert_eq!(state.stage, NotchStage::Expanded);
+        state.set_phase(Expanded);
+        assert_eq!(state.stage, NotchStage::Expanded);
+        state.set_phase(Collapsing);
+        assert_eq!(state.stage, NotchStage::Compact);
+        state.set_phase(Collapsed);
+        assert_eq!((state.phase, state.stage), (Collapsed, NotchStage::Compact));
+
+        // Peek is a collapsed stage, so settling collapsed keeps it
+        state.stage = NotchStage::Peek;
+        state.set_phase(Collapsed);
+        assert_eq!(state.stage, NotchStage::Peek);
+    }
+
+    #[test]
+    fn settling_collapsed_unpins() {
+        let mut state = NotchState {
+            pinned: true,
+            ..NotchState::default()
+        };
+        state.set_phase(Expanded);
+        state.set_phase(Collapsing);
+        assert!(state.pinned);
+        state.set_phase(Collapsed);
+        assert!(!state.pinned);
+    }
+
+    // Applies `change`, returning whether it changed anything and what was emitted
+    fn apply(store: &NotchStore, change: impl FnOnce(&mut NotchState)) -> (bool, Vec<NotchState>) {
+        let mut emitted = Vec::new();
+        let changed = store.apply(change, |state| emitted.push(state.clone()));
+        (changed, emitted)
+    }
+
+    #[test]
+    fn update_emits_each_change_once() {
+        let store = NotchStore::default();
+        let (changed, emitted) = apply(&store, |state| state.stage = NotchStage::Peek);
+        assert!(changed);
+        assert_eq!(emitted, vec![store.get()]);
+        assert_eq!(store.get().stage, NotchStage::Peek);
+
+        // Setting what's already there
+        let (changed, emitted) = apply(&store, |state| state.stage = NotchStage::Peek);
+        assert!(!changed);
+        assert!(emitted.is_empty());
+    }
+
+    #[test]
+    fn update_mirrors_zones_and_pinned() {
+        let store = NotchStore::default();
+        let (zones, pinned) = (store.zone_stage(), store.pinned());
+
+        apply(&store, |state| state.stage = NotchStage::Peek);
+        assert_eq!(zones.load(), (NotchStage::Peek, NotchView::App));
+
+        // Expanding keeps the zones it started from until the window is open
+        apply(&store, |state| state.set_phase(Expanding));
+        assert_eq!(zones.load().0, NotchStage::Peek);
+        apply(&store, |state| {
+            state.set_phase(Expanded);
+            state.active_view = NotchView::Settings;
+            state.pinned = true;
+        });
+        assert_eq!(zones.load(), (NotchStage::Expanded, NotchView::Settings));
+        assert!(pinned.load(Ordering::Relaxed));
+
+        // and collapsing keeps the expanded ones until it's closed
+        apply(&store, |state| state.set_phase(Collapsing));
+        assert_eq!(zones.load().0, NotchStage::Expanded);
+        apply(&store, |state| state.set_phase(Collapsed));
+        assert_eq!(zones.load().0, NotchStage::Compact);
+        assert!(!pinned.load(Ordering::Relaxed));
+    }
+}
@@ -1,11 +1,6 @@
 import AppKit
 import QuartzCore
 
-@objc public enum NotchPhase: Int { 
-    case expand
-    case collapse 
-}
-
 // Config structures matching the JSON config file
 struct NotchConfig: Codable {
     let animation: AnimationConfig
@@ -14,11 +9,29 @@ struct NotchConfig: Codable {
     let window: WindowConfig
 }
 
+// Only the curves Rust solves from the animation models are read here
 struct AnimationConfig: Codable {
-    let expand_duration: ConfigValue<Double>
-    let collapse_duration: ConfigValue<Double>
-    let expand_timing: ConfigValue<[Double]>
-    let collapse_timing: ConfigValue<[Double]>
+    let solved: SolvedAnimations
+}
+
+struct SolvedAnimations: Codable {
+    let expand: AnimationCurve
+    let collapse: AnimationCurve
+}
+
+// Progress samples evenly spaced from 0 to settle_time
+struct AnimationCurve: Codable {
+    let settle_time: Double
+    let samples: [Double]
+
+    // Progress at `elapsed` seconds, interpolating between samples
+    func progress(at elapsed: Double) -> Double {
+        guard elapsed < settle_time, samples.count > 1 else { return 1 }
+        let position = max(0, elapsed) / settle_time * Double(samples.count - 1)
+        let index = Int(position)
+        let fraction = position - Double(index)
+        return samples[index] + (samples[index + 1] - samples[index]) * fraction
+    }
 }
 
 struct DimensionsConfig: Codable {
@@ -56,13 +69,16 @@ struct ConfigValue<T: Codable>: Codable {
     private var expandedRect: CGRect = .zero
     private var corner: CGFloat = 12
     
-    // Animation timing from config - defaults matching Boring Notch feel
-    private var expandDuration: CFTimeInterval = 0.4
-    private var collapseDuration: CFTimeInterval = 0.3
-    private var expandTimingFunction = CAMediaTimingFunction(controlPoints: 0.16, 1.0, 0.3, 1.0)
-    private var collapseTimingFunction = CAMediaTimingFunction(controlPoints: 0.25, 0.1, 0.25, 1.0)
+    // Solved animation curves from config; until it arrives, straight lines
+    private var expandCurve = AnimationCurve(settle_time: 0.4, samples: [0, 1])
+    private var collapseCurve = AnimationCurve(settle_time: 0.3, samples: [0, 1])
     private var windowLevelOffset: Int = 3
-    private var useSpringAnimation: Bool = true
+
+    // The running animation, to find where the mask is if it's interrupted
+    private var size: CGSize = .zero
+    private var running: (from: CGSize, to: CGSize, start: CFTimeInterval, curve: AnimationCurve)?
+    // Bumped by every move, so an interrupted animation doesn't report ending
+    private var generation = 0
 
     @objc public func attach(to window: NSWindow, closedRect: CGRect, expandedRect: CGRect, corner: CGFloat) {
         self.window = window
@@ -118,29 +134,8 @@ struct ConfigValue<T: Codable>: Codable {
             let config = try decoder.decode(NotchConfig.self, from: jsonData)
             
             // Apply animation config
-            expandDuration = config.animation.expand_duration.value
-            collapseDuration = config.animation.collapse_duration.value
-            
-            // Apply timing functions from control points
-            let expandPoints = config.animation.expand_timing.value
-            if expandPoints.count == 4 {
-                expandTimingFunction = CAMediaTimingFunction(
-                    controlPoints: Float(expandPoints[0]),
-                    Float(expandPoints[1]),
-                    Float(expandPoints[2]),
-                    Float(expandPoints[3])
-                )
-            }
-            
-            let collapsePoints = config.animation.collapse_timing.value
-            if collapsePoints.count == 4 {
-                collapseTimingFunction = CAMediaTimingFunction(
-                    controlPoints: Float(collapsePoints[0]),
-                    Float(collapsePoints[1]),
-                    Float(collapsePoints[2]),
-                    Float(collapsePoints[3])
-                )
-            }
+            expandCurve = config.animation.solved.expand
+            collapseCurve = config.animation.solved.collapse
             
             // Apply dimensions config
             corner = config.dimensions.corner_radius.value
@@ -153,9 +148,7 @@ struct ConfigValue<T: Codable>: Codable {
             
             // Refresh path with new corner radius if we have a current progress
             if maskLayer.path != nil {
-                // Get current progress - if collapsed it's 0, if expanded it's 1
-                let currentProgress: CGFloat = (closedRect.width == expandedRect.width) ? 0 : 1
-                updatePath(progress: currentProgress)
+                updatePath(size: size)
             }
             
             print("✓ Successfully loaded animation config from JSON")
@@ -178,92 +171,137 @@ struct ConfigValue<T: Codable>: Codable {
         }
     }
 
-    @objc public func expand(duration: CFTimeInterval, appHandle: UnsafeMutableRawPointer?) {
-        animate(to: 1.0, duration: expandDuration, timingFunction: expandTimingFunction)
-        // Use actual animation duration for spring animation
-        let actualDuration: CFTimeInterval
-        if #available(macOS 14.0, *), useSpringAnimation {
-            actualDuration = 0.5 // Spring settling time
-        } else {
-            actualDuration = expandDuration
-        }
-        notifyEnd(phase: .expand, after: actualDuration, appHandle: appHandle)
+    // Animates to a stage's size from wherever the mask is, with the solved
+    // expand curve when growing and the collapse curve when shrinking, then
+    // hands `stage` back to Rust
+    @objc public func animate(toWidth width: CGFloat, height: CGFloat, growing: Bool, stage: Int32, appHandle: UnsafeMutableRawPointer?) {
+        let curve = growing ? expandCurve : collapseCurve
+        animate(to: CGSize(width: width, height: height), curve: curve)
+        notifyEnd(stage: stage, after: curve.settle_time, appHandle: appHandle)
     }
 
-    @objc public func collapse(duration: CFTimeInterval, appHandle: UnsafeMutableRawPointer?) {
-        animate(to: 0.0, duration: collapseDuration, timingFunction: collapseTimingFunction)
-        // Use actual animation duration for spring animation
-        let actualDuration: CFTimeInterval
-        if #available(macOS 14.0, *), useSpringAnimation {
-            actualDuration = 0.35 // Spring settling time
-        } else {
-            actualDuration = collapseDuration
-        }
-        notifyEnd(phase: .collapse, after: actualDuration, appHandle: appHandle)
+    @objc public func setWidth(_ width: CGFloat, height: CGFloat) {
+        running = nil
+        generation += 1
+        maskLayer.removeAllAnimations()
+        updatePath(size: CGSize(width: width, height: height))
+    }
+
+    // A tab with its own expanded size; progress runs up to it from now on
+    @objc public func setExpandedWidth(_ width: CGFloat, height: CGFloat) {
+        expandedRect.size = CGSize(width: width, height: height)
     }
 
     @objc public func setProgress(_ p: CGFloat) { 
+        running = nil
+        generation += 1
+        maskLayer.removeAllAnimations()
         updatePath(progress: max(0, min(1, p))) 
     }
 
-    private func animate(to target: CGFloat, duration: CFTimeInterval, timingFunction: CAMediaTimingFunction) {
+    // Where the mask is now, part way through an animation or not
+    private func currentSize() -> CGSize {
+        guard let running = running else { return size }
+        let eased = CGFloat(running.curve.progress(at: CACurrentMediaTime() - running.start))
+        return lerp(running.from, running.to, eased)
+    }
+
+    private func lerp(_ from: CGSize, _ to: CGSize, _ t: CGFloat) -> CGSize {
+        CGSize(width: from.width + (to.width - from.width) * t,
+               height: from.height + (to.height - from.height) * t)
+    }
+
+    private func animate(to target: CGSize, curve: AnimationCurve) {
         guard let contentView = window?.contentView else { return }
         contentView.layoutSubtreeIfNeeded()
+        // The window may have been resized for this stage or tab
+        if let layer = contentView.layer {
+            maskLayer.frame = layer.bounds
+        }
+
+        // Start from wherever an interrupted animation left the mask
+        let from = currentSize()
+        let paths = curve.samples.map { sample -> CGPath in
+            updatePath(size: lerp(from, target, CGFloat(sample)))
+            return maskLayer.path!
+        }
+        updatePath(size: target)
 
-        let fromPath = maskLayer.path
-        updatePath(progress: target)
-        let toPath = maskLayer.path
-        
         // Remove any existing animations to prevent conflicts
         maskLayer.removeAllAnimations()
-        maskLayer.path = toPath
-
-        if #available(macOS 14.0, *), useSpringAnimation {
-            // Use spring animation for smoother, more natural feel
-            let anim = CASpringAnimation(keyPath: "path")
-            anim.fromValue = fromPath
-            anim.toValue = toPath
-            anim.mass = 1.0
-            anim.stiffness = 300.0
-            anim.damping = 30.0
-            anim.duration = anim.settlingDuration
-            anim.fillMode = .forwards
-            anim.isRemovedOnCompletion = false
-            maskLayer.add(anim, forKey: "path")
-        } else {
-            // Fallback to timing function for older macOS
-            let anim = CABasicAnimation(keyPath: "path")
-            anim.fromValue = fromPath
-            anim.toValue = toPath
-            anim.duration = duration
-            anim.timingFunction = timingFunction
-            anim.fillMode = .forwards
-            anim.isRemovedOnCompletion = false
-            maskLayer.add(anim, forKey: "path")
-        }
+        running = (from, target, CACurrentMediaTime(), curve)
+        generation += 1
+
+        let anim = CAKeyframeAnimation(keyPath: "path")
+        anim.values = paths
+        anim.duration = curve.settle_time
+        anim.calculationMode = .linear
+        anim.fillMode = .forwards
+        anim.isRemovedOnCompletion = false
+        maskLayer.add(anim, forKey: "path")
     }
 
     private func updatePath(progress: CGFloat) {
-        // Lerp rect and corner
-        let w = closedRect.width  + (expandedRect.width  - closedRect.width)  * progress
-        let h = closedRect.height + (expandedRect.height - closedRect.height) * progress
-        let rect = CGRect(x: (expandedRect.width - w) / 2.0,
-                          y: (expandedRect.height - h), // anchor to top edge; grow downward
-                          width: w, height: h)
-
-        let path = CGPath(roundedRect: rect, cornerWidth: corner, cornerHeight: corner, transform: nil)
+        updatePath(size: lerp(closedRect.size, expandedRect.size, progress))
+    }
+
+    private func updatePath(size: CGSize) {
+        // Centred in the window as it is now, which is at least as big as the mask
+        let bounds = window?.contentView?.bounds.size ?? expandedRect.size
+        let rect = CGRect(x: (bounds.width - size.width) / 2.0,
+                          y: (bounds.height - size.height), // anchor to top edge; grow downward
+                          width: size.width, height: size.height)
+
+        let path = silhouettePath(in: rect)
+            ?? CGPath(roundedRect: rect, cornerWidth: corner, cornerHeight: corner, transform: nil)
         maskLayer.path = path
         hitView.currentPath = path
+        self.size = size
+    }
+
+    // The Rust silhouette (concave shoulders plus rounded bottom corners) filling
+    // `rect`, the outline the CSS masks and hover zones use too. Nil without the
+    // Rust symbol, e.g. in a standalone build
+    private func silhouettePath(in rect: CGRect) -> CGPath? {
+        guard let symbol = dlsym(UnsafeMutableRawPointer(bitPattern: -2), "_notch_silhouette_polygon") else {
+            return nil
+        }
+        typealias PolygonFn = @convention(c) (Double, Double, Double, Double, UnsafeMutablePointer<Double>?, Int) -> Int
+        let polygon = unsafeBitCast(symbol, to: PolygonFn.self)
+
+        // How far between closed and expanded, which sets the shoulders
+        let span = expandedRect.height - closedRect.height
+        let progress = span > 0 ? max(0, min(1, (rect.height - closedRect.height) / span)) : 0
+        let args = (Double(rect.width), Double(rect.height), Double(corner), Double(progress))
+        var count = polygon(args.0, args.1, args.2, args.3, nil, 0)
+        guard count > 2 else { return nil }
+        var points = [Double](repeating: 0, count: count * 2)
+        count = min(count, polygon(args.0, args.1, args.2, args.3, &points, count))
+
+        let path = CGMutablePath()
+        for i in 0..<count {
+            // Rust is y-down from the top edge; the layer is y-up
+            let point = CGPoint(x: rect.minX + CGFloat(points[2 * i]), y: rect.maxY - CGFloat(points[2 * i + 1]))
+            if i == 0 {
+                path.move(to: point)
+            } else {
+                path.addLine(to: point)
+            }
+        }
+        path.closeSubpath()
+        return path
     }
 
-    private func notifyEnd(phase: NotchPhase, after: CFTimeInterval, appHandle: UnsafeMutableRawPointer?) {
+    private func notifyEnd(stage: Int32, after: CFTimeInterval, appHandle: UnsafeMutableRawPointer?) {
         guard let appHandle = appHandle else { return }
-        DispatchQueue.main.asyncAfter(deadline: .now() + after) {
+        let generation = self.generation
+        DispatchQueue.main.asyncAfter(deadline: .now() + after) { [weak self] in
+            guard let self = self, self.generation == generation else { return }
             // Call the Rust function directly via dlsym
             if let rustCallback = dlsym(UnsafeMutableRawPointer(bitPattern: -2), "_notch_notify_anim_end") {
                 typealias CallbackType = @convention(c) (UnsafeMutableRawPointer, Int32) -> Void
                 let callback = unsafeBitCast(rustCallback, to: CallbackType.self)
-                callback(appHandle, Int32(phase.rawValue))
+                callback(appHandle, stage)
             }
         }
     }
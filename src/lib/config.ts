@@ -1,3 +1,6 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TabId } from './stores/tabs';
+
 /**
  * Universal config that matches notch-config.json structure
  * 
@@ -21,10 +24,13 @@
  */
 export interface NotchConfig {
 	animation: {
-		expand_duration: ConfigValue<number>;
-		collapse_duration: ConfigValue<number>;
-		expand_timing: ConfigValue<number[]>;
-		collapse_timing: ConfigValue<number[]>;
+		expand: ConfigValue<AnimationModel>;
+		collapse: ConfigValue<AnimationModel>;
+		/** Solved by Rust; only present when the config came from `get_notch_config` */
+		solved?: {
+			expand: AnimationCurve;
+			collapse: AnimationCurve;
+		};
 	};
 	dimensions: {
 		corner_radius: ConfigValue<number>;
@@ -32,19 +38,116 @@ export interface NotchConfig {
 		collapsed_height: ConfigValue<number>;
 		expanded_width: ConfigValue<number>;
 		expanded_height: ConfigValue<number>;
+		peek_width: ConfigValue<number>;
+		peek_height: ConfigValue<number>;
+		views?: ConfigValue<Partial<Record<TabId, ViewSize>>>;
 	};
 	hover: {
 		collapsed_zone_width: ConfigValue<number>;
 		collapsed_zone_height: ConfigValue<number>;
 		expanded_zone_width: ConfigValue<number>;
 		expanded_zone_height: ConfigValue<number>;
+		peek_zone_width: ConfigValue<number>;
+		peek_zone_height: ConfigValue<number>;
 		expand_delay_ms: ConfigValue<number>;
 		collapse_delay_ms: ConfigValue<number>;
 		poll_interval_ms: ConfigValue<number>;
+		idle_poll_interval_ms: ConfigValue<number>;
+		near_distance: ConfigValue<number>;
+		velocity_threshold: ConfigValue<number>;
+		zone: {
+			shape: ConfigValue<'rectangle' | 'rounded' | 'trapezoid'>;
+			corner_radius: ConfigValue<number>;
+			taper: ConfigValue<number>;
+			exit_margin: ConfigValue<number>;
+		};
 	};
 	window: {
 		level_offset: ConfigValue<number>;
 	};
+	audio: {
+		source: ConfigValue<string>;
+		device: ConfigValue<string>;
+		bands: ConfigValue<number>;
+		fft_size: ConfigValue<number>;
+		min_frequency: ConfigValue<number>;
+		max_frequency: ConfigValue<number>;
+		smoothing: ConfigValue<number>;
+		max_fps: ConfigValue<number>;
+	};
+	media: {
+		sources: {
+			allow: ConfigValue<string[]>;
+			deny: ConfigValue<string[]>;
+			min_duration: ConfigValue<number>;
+		};
+		mock: {
+			script: ConfigValue<string>;
+		};
+	};
+	activation: {
+		mode: ConfigValue<'hover' | 'click' | 'hover_with_modifier' | 'hybrid'>;
+		modifier: ConfigValue<'shift' | 'control' | 'option' | 'command'>;
+	};
+	pin: {
+		persist: ConfigValue<boolean>;
+	};
+	peek: {
+		dismiss_ms: ConfigValue<number>;
+	};
+	synthetic_notch: {
+		width: ConfigValue<number>;
+		inset: ConfigValue<number>;
+		scale: ConfigValue<number>;
+	};
+}
+
+export type AnimationModel =
+	| { model: 'bezier'; duration: number; points: number[] }
+	| { model: 'spring'; stiffness: number; damping: number; mass: number; initial_velocity: number };
+
+/**
+ * Progress samples evenly spaced from 0 to `settle_time` seconds, solved from an
+ * `AnimationModel` by Rust
+ */
+export interface AnimationCurve {
+	settle_time: number;
+	samples: number[];
+}
+
+/**
+ * Progress `seconds` into a solved curve, interpolating between samples
+ */
+export function curveProgress(curve: AnimationCurve, seconds: number): number {
+	const { samples, settle_time } = curve;
+	if (seconds >= settle_time || samples.length < 2) {
+		return 1;
+	}
+	const position = (Math.max(0, seconds) / settle_time) * (samples.length - 1);
+	const index = Math.floor(position);
+	const fraction = position - index;
+	return samples[index] + (samples[index + 1] - samples[index]) * fraction;
+}
+
+/**
+ * An expanded size in points for one tab
+ */
+export interface ViewSize {
+	width: number;
+	height: number;
+}
+
+/**
+ * The expanded size for `view`, falling back to `expanded_width` × `expanded_height`
+ */
+export function expandedSize(config: NotchConfig, view: TabId): ViewSize {
+	const { dimensions } = config;
+	return (
+		dimensions.views?.value[view] ?? {
+			width: dimensions.expanded_width.value,
+			height: dimensions.expanded_height.value
+		}
+	);
 }
 
 export interface ConfigValue<T> {
@@ -55,14 +158,22 @@ export interface ConfigValue<T> {
 let cachedConfig: NotchConfig | null = null;
 
 /**
- * Load the notch config from JSON file
- * This reads the same config file used by Rust and Swift
+ * Load the notch config from Rust, which adds the solved animation curves, or
+ * failing that straight from the JSON file Rust and Swift read
  */
 export async function loadConfig(): Promise<NotchConfig> {
 	if (cachedConfig) {
 		return cachedConfig;
 	}
 
+	try {
+		// From Rust first: it adds the solved animation curves
+		cachedConfig = await invoke<NotchConfig>('get_notch_config');
+		return cachedConfig;
+	} catch (error) {
+		console.warn('Config not available from Rust, loading the JSON file:', error);
+	}
+
 	try {
 		// In production, the config will be bundled
 		// In dev, fetch from root
@@ -91,21 +202,15 @@ export function getConfig(): NotchConfig {
 function getDefaultConfig(): NotchConfig {
 	return {
 		animation: {
-			expand_duration: {
-				value: 0.5,
-				description: 'Duration in seconds for the expand animation - slower gives more fluid spring feel'
-			},
-			collapse_duration: {
-				value: 0.35,
-				description: 'Duration in seconds for the collapse animation - smooth and gentle'
+			expand: {
+				value: { model: 'spring', stiffness: 300.0, damping: 30.0, mass: 1.0, initial_velocity: 0.0 },
+				description:
+					'Expand animation: a bezier (duration in seconds, points [x1, y1, x2, y2]) or a spring (stiffness, damping, mass, initial_velocity), picked by model'
 			},
-			expand_timing: {
-				value: [0.16, 1.0, 0.3, 1.0],
-				description: 'Cubic bezier control points for expand animation (fluid spring curve)'
-			},
-			collapse_timing: {
-				value: [0.25, 0.1, 0.25, 1.0],
-				description: 'Cubic bezier control points for collapse animation (smooth ease out)'
+			collapse: {
+				value: { model: 'bezier', duration: 0.35, points: [0.25, 0.1, 0.25, 1.0] },
+				description:
+					'Collapse animation: a bezier (duration in seconds, points [x1, y1, x2, y2]) or a spring (stiffness, damping, mass, initial_velocity), picked by model'
 			}
 		},
 		dimensions: {
@@ -128,6 +233,22 @@ function getDefaultConfig(): NotchConfig {
 			expanded_height: {
 				value: 200.0,
 				description: 'Height in points when the notch is fully expanded'
+			},
+			peek_width: {
+				value: 520.0,
+				description: 'Width in points at the peek stage, between collapsed and expanded'
+			},
+			peek_height: {
+				value: 90.0,
+				description: 'Height in points at the peek stage, between collapsed and expanded'
+			},
+			views: {
+				value: {
+					archive: { width: 700.0, height: 320.0 },
+					settings: { width: 700.0, height: 360.0 }
+				},
+				description:
+					'Expanded size in points per tab (app, archive, settings); tabs without one use expanded_width and expanded_height'
 			}
 		},
 		hover: {
@@ -147,6 +268,14 @@ function getDefaultConfig(): NotchConfig {
 				value: 200.0,
 				description: 'Height in points of the hover detection zone when expanded'
 			},
+			peek_zone_width: {
+				value: 520.0,
+				description: 'Width in points of the hover detection zone at the peek stage'
+			},
+			peek_zone_height: {
+				value: 90.0,
+				description: 'Height in points of the hover detection zone at the peek stage'
+			},
 			expand_delay_ms: {
 				value: 250,
 				description: 'Milliseconds to wait before expanding when hovering over the notch area'
@@ -157,7 +286,42 @@ function getDefaultConfig(): NotchConfig {
 			},
 			poll_interval_ms: {
 				value: 50,
-				description: 'How often in milliseconds to check mouse position (polling fallback)'
+				description:
+					'How often in milliseconds to check mouse position while the pointer is near a hover zone (polling fallback)'
+			},
+			idle_poll_interval_ms: {
+				value: 250,
+				description:
+					'How often in milliseconds to check mouse position while the pointer is far from every hover zone'
+			},
+			near_distance: {
+				value: 200.0,
+				description: 'Distance in points from a hover zone within which polling switches to poll_interval_ms'
+			},
+			velocity_threshold: {
+				value: 1200.0,
+				description:
+					"Pointer speed in points per second above which entering the zone doesn't arm expansion unless the pointer slows down inside it (0 = off)"
+			},
+			zone: {
+				shape: {
+					value: 'rectangle',
+					description:
+						'Hover zone shape: rectangle, rounded (rounded bottom corners), trapezoid (narrows toward the bottom like the notch), or silhouette (the capsule outline)'
+				},
+				corner_radius: {
+					value: 16.0,
+					description: 'Bottom corner radius in points for the rounded shape'
+				},
+				taper: {
+					value: 40.0,
+					description: 'How far in points each side of the trapezoid narrows toward the bottom'
+				},
+				exit_margin: {
+					value: 8.0,
+					description:
+						'Extra points around the zone the pointer must cross before it counts as leaving (hysteresis)'
+				}
 			}
 		},
 		window: {
@@ -165,6 +329,105 @@ function getDefaultConfig(): NotchConfig {
 				value: 3,
 				description: 'How many levels above main menu to place the window (higher = more on top)'
 			}
+		},
+		audio: {
+			source: {
+				value: 'pulse',
+				description:
+					'PCM source for the waveform spectrum: pulse (parec monitor), pipewire (pw-record), wav:<path> for testing, or off'
+			},
+			device: {
+				value: '',
+				description: 'Capture device or sink name; empty uses the default output monitor'
+			},
+			bands: {
+				value: 5,
+				description: 'Number of log-spaced frequency bands emitted per frame'
+			},
+			fft_size: {
+				value: 2048,
+				description: 'FFT window size in samples (rounded up to a power of two)'
+			},
+			min_frequency: {
+				value: 40.0,
+				description: 'Lowest frequency in Hz covered by the first band'
+			},
+			max_frequency: {
+				value: 12000.0,
+				description: 'Highest frequency in Hz covered by the last band'
+			},
+			smoothing: {
+				value: 0.6,
+				description: 'Band smoothing between frames (0 = none, close to 1 = very smooth)'
+			},
+			max_fps: {
+				value: 30,
+				description: 'Maximum spectrum frames emitted per second while expanded and playing'
+			}
+		},
+		media: {
+			sources: {
+				allow: {
+					value: [],
+					description:
+						'Player patterns (identity or bus name, * and ? wildcards) allowed in the capsule; empty allows every player'
+				},
+				deny: {
+					value: [],
+					description:
+						'Player patterns (identity or bus name, * and ? wildcards) that never show in the capsule'
+				},
+				min_duration: {
+					value: 0,
+					description:
+						'Ignore tracks shorter than this many seconds (0 = off; unknown lengths always pass)'
+				}
+			},
+			mock: {
+				script: {
+					value: '',
+					description:
+						'Path to a mock player script (e.g. mock-media/script.json) that replaces the real media backend; empty = off. NOTCH_MEDIA_MOCK overrides it'
+				}
+			}
+		},
+		activation: {
+			mode: {
+				value: 'hover',
+				description:
+					'How the capsule expands: hover, click, hover_with_modifier, or hybrid (hover or click)'
+			},
+			modifier: {
+				value: 'option',
+				description: 'Key to hold in hover_with_modifier mode: shift, control, option, or command'
+			}
+		},
+		pin: {
+			persist: {
+				value: false,
+				description: 'Remember whether the capsule is pinned open across restarts'
+			}
+		},
+		peek: {
+			dismiss_ms: {
+				value: 3000,
+				description: 'Milliseconds the capsule stays at the peek stage before returning to compact'
+			}
+		},
+		synthetic_notch: {
+			width: {
+				value: 200.0,
+				description: 'Width in points of the notch assumed on displays without one'
+			},
+			inset: {
+				value: 0.0,
+				description:
+					'Height in points of the assumed notch (0 = the menu bar or top panel height, or 32 without one)'
+			},
+			scale: {
+				value: 0.0,
+				description: "Scale factor for the assumed notch's pixel sizes (0 = the display's)"
+			}
 		}
 	};
 }
@@ -8,6 +8,20 @@ export const notchExpandedHeight = getConfig().dimensions.expanded_height.value;
 export const DEV_KEEP_NOTCH_EXPANDED = false;
 
 export { activeTab, type TabId } from './stores/tabs';
+export { notchPinned, setNotchPinned } from './stores/pin';
+export {
+	notchState,
+	notchPhase,
+	notchStage,
+	syncNotchState,
+	setNotchPhase,
+	setNotchView,
+	peekNotch,
+	type NotchState,
+	type NotchPhase,
+	type NotchStage,
+	type NotchDisplay
+} from './stores/notch';
 export { default as AppView } from './components/views/app-view.svelte';
 export { default as ArchiveView } from './components/views/archive-view.svelte';
 export { default as SettingsView } from './components/views/settings-view.svelte';
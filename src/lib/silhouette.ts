@@ -0,0 +1,32 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// The capsule outline from Rust (`get_notch_silhouette`), in points
+export interface NotchSilhouette {
+	path: string;
+	polygon: [number, number][];
+}
+
+// Used until Rust answers, or outside Tauri
+const FALLBACK_PATH =
+	'M120 4C120 1.79086 121.791 0 124 0H127V0H0V0H3C5.20914 0 7 1.79086 7 4V14C7 17.3137 9.68629 20 13 20H114C117.314 20 120 17.3137 120 14V4Z';
+
+function maskUri(path: string, width: number, height: number) {
+	const svg = `<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 ${width} ${height}" preserveAspectRatio="none"><path d="${path}" fill="white"/></svg>`;
+	return `url("data:image/svg+xml,${encodeURIComponent(svg)}")`;
+}
+
+export const fallbackMaskUri = maskUri(FALLBACK_PATH, 127, 20);
+
+/**
+ * A CSS mask image of the capsule at `width` × `height`, `progress` of the way
+ * from compact (0) to expanded (1). The same outline backs the `silhouette`
+ * hover zone shape.
+ */
+export async function silhouetteMask(width: number, height: number, progress: number) {
+	const silhouette = await invoke<NotchSilhouette>('get_notch_silhouette', {
+		width,
+		height,
+		progress
+	}).catch(() => null);
+	return silhouette ? maskUri(silhouette.path, width, height) : fallbackMaskUri;
+}
@@ -0,0 +1,65 @@
+import { derived, writable } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { TabId } from './tabs';
+
+export type NotchPhase = 'collapsed' | 'expanding' | 'expanded' | 'collapsing';
+
+// How big the capsule is; phase still tracks the expand/collapse animation
+export type NotchStage = 'compact' | 'peek' | 'expanded';
+
+// The monitor hosting the capsule, in physical pixels
+export interface NotchDisplay {
+	name: string | null;
+	x: number;
+	y: number;
+	width: number;
+	height: number;
+	scale_factor: number;
+}
+
+export interface NotchState {
+	phase: NotchPhase;
+	stage: NotchStage;
+	pinned: boolean;
+	focused: boolean;
+	active_view: TabId;
+	display: NotchDisplay | null;
+}
+
+// Mirrors the state owned by Rust; kept in sync through notch-state-changed
+export const notchState = writable<NotchState>({
+	phase: 'collapsed',
+	stage: 'compact',
+	pinned: false,
+	focused: false,
+	active_view: 'app',
+	display: null
+});
+
+export const notchPhase = derived(notchState, ($state) => $state.phase);
+export const notchStage = derived(notchState, ($state) => $state.stage);
+
+// Starts mirroring the Rust state; resolves with the unlisten function
+export async function syncNotchState() {
+	const unlisten = await listen<NotchState>('notch-state-changed', ({ payload }) => {
+		notchState.set(payload);
+	});
+	const current = await invoke<NotchState>('get_notch_state').catch(() => null);
+	if (current) notchState.set(current);
+	return unlisten;
+}
+
+// Reports that an expand/collapse animation started or finished
+export function setNotchPhase(phase: NotchPhase) {
+	return invoke('set_notch_phase', { phase }).catch(() => {});
+}
+
+// Briefly shows the peek stage; Rust dismisses it after peek.dismiss_ms
+export function peekNotch() {
+	return invoke('notch_peek');
+}
+
+export function setNotchView(view: TabId) {
+	return invoke('set_notch_view', { view }).catch(() => {});
+}
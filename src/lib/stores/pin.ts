@@ -0,0 +1,10 @@
+import { derived } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import { notchState } from './notch';
+
+// Pinned state is part of the Rust-owned notch state
+export const notchPinned = derived(notchState, ($state) => $state.pinned);
+
+export function setNotchPinned(pinned: boolean) {
+	return invoke('set_notch_pinned', { pinned }).catch(() => {});
+}